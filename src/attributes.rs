@@ -0,0 +1,74 @@
+use crate::CHAR_WIDTH;
+use core::ops::{BitOr, BitOrAssign};
+
+const ROWS_MASK: u8 = 0x7F;
+const UNDERLINE_ROW: u8 = 0x40;
+
+// Set of per character attributes packed into a byte, combined with |, e.g.
+// TextAttributes::BLINK | TextAttributes::UNDERLINE.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TextAttributes(u8);
+
+impl TextAttributes {
+    pub const NONE: TextAttributes = TextAttributes(0b0000);
+    // hidden while the blink phase is off, see tick_blink
+    pub const BLINK: TextAttributes = TextAttributes(0b0001);
+    // lit and unlit dots swapped
+    pub const INVERSE: TextAttributes = TextAttributes(0b0010);
+    // bottom row lit, overlaying any descenders
+    pub const UNDERLINE: TextAttributes = TextAttributes(0b0100);
+    // each column OR'd into the one to its right
+    pub const BOLD: TextAttributes = TextAttributes(0b1000);
+
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    // unknown bits are dropped
+    pub const fn from_bits(bits: u8) -> Self {
+        TextAttributes(bits & 0b1111)
+    }
+
+    pub const fn contains(self, other: TextAttributes) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub const fn union(self, other: TextAttributes) -> Self {
+        TextAttributes(self.0 | other.0)
+    }
+
+    pub(crate) fn apply(self, mut cell: [u8; CHAR_WIDTH], blink_visible: bool) -> [u8; CHAR_WIDTH] {
+        if self.contains(Self::BLINK) && !blink_visible {
+            return [0x00; CHAR_WIDTH];
+        }
+
+        if self.contains(Self::BOLD) {
+            for col in (1..CHAR_WIDTH).rev() {
+                cell[col] |= cell[col - 1];
+            }
+        }
+        for dots in cell.iter_mut() {
+            if self.contains(Self::UNDERLINE) {
+                *dots |= UNDERLINE_ROW;
+            }
+            if self.contains(Self::INVERSE) {
+                *dots = !*dots & ROWS_MASK;
+            }
+        }
+        cell
+    }
+}
+
+impl BitOr for TextAttributes {
+    type Output = TextAttributes;
+
+    fn bitor(self, other: TextAttributes) -> TextAttributes {
+        self.union(other)
+    }
+}
+
+impl BitOrAssign for TextAttributes {
+    fn bitor_assign(&mut self, other: TextAttributes) {
+        *self = self.union(other);
+    }
+}
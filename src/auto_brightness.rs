@@ -0,0 +1,146 @@
+use crate::luminance::LUMINANCE_MAX;
+
+// Source of ambient light readings, e.g. an ambient light sensor driver or a scaled ADC reading of
+// a photodiode. Closures returning lux can be used directly.
+pub trait LightSensor {
+    type Error;
+
+    fn read_lux(&mut self) -> Result<u32, Self::Error>;
+}
+
+impl<F: FnMut() -> u32> LightSensor for F {
+    type Error = core::convert::Infallible;
+
+    fn read_lux(&mut self) -> Result<u32, Self::Error> {
+        Ok(self())
+    }
+}
+
+// lux to luminance level points, dim indoors at night up to full brightness in daylight
+pub const DEFAULT_BRIGHTNESS_CURVE: &[(u32, u8)] = &[
+    (0, 4),
+    (10, 12),
+    (100, 28),
+    (1_000, 48),
+    (10_000, LUMINANCE_MAX),
+];
+
+const DEFAULT_SMOOTHING: u8 = 3;
+const DEFAULT_HYSTERESIS: u8 = 1;
+// fractional bits kept in the smoothed lux
+const LUX_FRACTION_BITS: u32 = 8;
+
+// Follows ambient light, choosing a set_luminance level from smoothed lux readings along a curve.
+// Call tick_auto_brightness at a fixed rate, e.g. every 100 ms.
+pub struct AutoBrightness<S: LightSensor> {
+    sensor: S,
+    curve: &'static [(u32, u8)],
+    smoothing: u8,
+    hysteresis: u8,
+    smoothed_lux: Option<u64>,
+    level: Option<u8>,
+}
+
+impl<S: LightSensor> AutoBrightness<S> {
+    pub fn new(sensor: S) -> Self {
+        AutoBrightness {
+            sensor,
+            curve: DEFAULT_BRIGHTNESS_CURVE,
+            smoothing: DEFAULT_SMOOTHING,
+            hysteresis: DEFAULT_HYSTERESIS,
+            smoothed_lux: None,
+            level: None,
+        }
+    }
+
+    // (lux, luminance level) points in increasing lux order, levels are interpolated between
+    // points and held beyond the ends
+    pub fn set_curve(&mut self, curve: &'static [(u32, u8)]) {
+        self.curve = curve;
+    }
+
+    // each reading moves the smoothed lux 1/2^smoothing of the way towards it, 0 disables
+    // smoothing
+    pub fn set_smoothing(&mut self, smoothing: u8) {
+        self.smoothing = smoothing.min(16);
+    }
+
+    // changes of this many levels or fewer are ignored, so readings near a step don't flicker
+    pub fn set_hysteresis(&mut self, levels: u8) {
+        self.hysteresis = levels;
+    }
+
+    pub fn lux(&self) -> Option<u32> {
+        self.smoothed_lux
+            .map(|lux| (lux >> LUX_FRACTION_BITS).min(u32::MAX as u64) as u32)
+    }
+
+    // the luminance level last chosen, None before the first reading
+    pub fn level(&self) -> Option<u8> {
+        self.level
+    }
+
+    pub fn sensor_mut(&mut self) -> &mut S {
+        &mut self.sensor
+    }
+
+    pub fn release(self) -> S {
+        self.sensor
+    }
+
+    // reads the sensor, returning the new luminance level when it should change
+    pub fn update(&mut self) -> Result<Option<u8>, S::Error> {
+        let lux = self.sensor.read_lux()?;
+        Ok(self.push_lux(lux))
+    }
+
+    // for readings taken outside of the sensor, returns the new luminance level when it should
+    // change
+    pub fn push_lux(&mut self, lux: u32) -> Option<u8> {
+        let sample = (lux as u64) << LUX_FRACTION_BITS;
+        let smoothed = match self.smoothed_lux {
+            Some(smoothed) if sample >= smoothed => {
+                smoothed + ((sample - smoothed) >> self.smoothing)
+            }
+            Some(smoothed) => smoothed - ((smoothed - sample) >> self.smoothing),
+            None => sample,
+        };
+        self.smoothed_lux = Some(smoothed);
+
+        let target = curve_level(self.curve, (smoothed >> LUX_FRACTION_BITS) as u32);
+        match self.level {
+            Some(level) if level.abs_diff(target) <= self.hysteresis => None,
+            _ => {
+                self.level = Some(target);
+                Some(target)
+            }
+        }
+    }
+
+    // forgets the smoothed lux, so the next reading is applied immediately
+    pub fn reset(&mut self) {
+        self.smoothed_lux = None;
+        self.level = None;
+    }
+}
+
+fn curve_level(curve: &[(u32, u8)], lux: u32) -> u8 {
+    let Some(&(first_lux, first_level)) = curve.first() else {
+        return LUMINANCE_MAX;
+    };
+    if lux <= first_lux {
+        return first_level.min(LUMINANCE_MAX);
+    }
+
+    for points in curve.windows(2) {
+        let [(low_lux, low_level), (high_lux, high_level)] = [points[0], points[1]];
+        if lux <= high_lux {
+            let (low_level, high_level) = (low_level as i64, high_level as i64);
+            let span = high_lux.saturating_sub(low_lux).max(1) as i64;
+            let offset = lux.saturating_sub(low_lux) as i64;
+            let level = low_level + ((high_level - low_level) * offset + span / 2) / span;
+            return (level as u8).min(LUMINANCE_MAX);
+        }
+    }
+    curve[curve.len() - 1].1.min(LUMINANCE_MAX)
+}
@@ -0,0 +1,71 @@
+use crate::CHAR_WIDTH;
+
+// bars use rows 1 to 5 so the outline and center marker stand out
+const BAR_COL: u8 = 0x3E;
+const OUTLINE_COL: u8 = 0x22;
+const CENTER_COL: u8 = 0x7F;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BarStyle {
+    #[default]
+    Solid,
+    // the unfilled part of the bar is drawn as an empty box
+    Outlined,
+    // lights whole characters, each with a gap column before the next
+    Segmented,
+    // centered on zero with a marker, negative values grow to the left and positive to the right
+    Bipolar,
+}
+
+// Renders value out of max as a bar into display columns, e.g. for print_cols. Values outside the
+// range are clamped, and negative values leave the bar empty unless the style is Bipolar.
+pub fn render_bar(cols: &mut [u8], value: i32, max: i32, style: BarStyle) {
+    cols.fill(0x00);
+    if cols.is_empty() {
+        return;
+    }
+
+    match style {
+        BarStyle::Solid => {
+            let filled = scale(value, max, cols.len());
+            cols[..filled].fill(BAR_COL);
+        }
+        BarStyle::Outlined => {
+            let filled = scale(value, max, cols.len());
+            let last = cols.len() - 1;
+            cols.fill(OUTLINE_COL);
+            cols[..filled].fill(BAR_COL);
+            cols[0] = BAR_COL;
+            cols[last] = BAR_COL;
+        }
+        BarStyle::Segmented => {
+            let lit = scale(value, max, cols.len().div_ceil(CHAR_WIDTH));
+            for segment in cols.chunks_mut(CHAR_WIDTH).take(lit) {
+                let width = segment.len().min(CHAR_WIDTH - 1);
+                segment[..width].fill(BAR_COL);
+            }
+        }
+        BarStyle::Bipolar => {
+            // the left half is a column shorter on even widths
+            let center = (cols.len() - 1) / 2;
+            cols[center] = CENTER_COL;
+            if value >= 0 {
+                let filled = scale(value, max, cols.len() - 1 - center);
+                cols[center + 1..center + 1 + filled].fill(BAR_COL);
+            } else {
+                let filled = scale(value.saturating_neg(), max, center);
+                cols[center - filled..center].fill(BAR_COL);
+            }
+        }
+    }
+}
+
+// units lit for value out of max, rounded to the nearest unit
+fn scale(value: i32, max: i32, units: usize) -> usize {
+    if max <= 0 || value <= 0 {
+        return 0;
+    }
+    let value = value.min(max) as u64;
+    let max = max as u64;
+    ((value * units as u64 + max / 2) / max) as usize
+}
@@ -0,0 +1,80 @@
+use embedded_hal::pwm::SetDutyCycle;
+
+// dimming levels are the fraction of time the display is unblanked, in 1/BLANK_DIM_MAX steps
+pub const BLANK_DIM_MAX: u8 = 16;
+
+// Dims the display below the lowest brightness by blanking it for part of each period, with
+// the lit ticks spread out as evenly as possible. Call tick_blank_dimming at 1 kHz or faster to
+// avoid visible flicker.
+#[derive(Debug, Clone)]
+pub struct BlankDimmer {
+    level: u8,
+    accumulator: u8,
+    lit: Option<bool>,
+}
+
+impl BlankDimmer {
+    // levels above BLANK_DIM_MAX are clamped
+    pub const fn new(level: u8) -> Self {
+        BlankDimmer {
+            level: if level > BLANK_DIM_MAX {
+                BLANK_DIM_MAX
+            } else {
+                level
+            },
+            accumulator: 0,
+            lit: None,
+        }
+    }
+
+    pub fn set_level(&mut self, level: u8) {
+        self.level = level.min(BLANK_DIM_MAX);
+    }
+
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+
+    // advances one tick, returning whether the display should now be lit when that changed
+    pub fn tick(&mut self) -> Option<bool> {
+        let lit = self.step();
+        if self.lit == Some(lit) {
+            return None;
+        }
+        self.lit = Some(lit);
+        Some(lit)
+    }
+
+    // advances one tick, returning whether the display should be lit
+    pub(crate) fn step(&mut self) -> bool {
+        self.accumulator += self.level;
+        let lit = self.accumulator >= BLANK_DIM_MAX;
+        if lit {
+            self.accumulator -= BLANK_DIM_MAX;
+        }
+        lit
+    }
+}
+
+// Dims the display with a hardware PWM channel driving the BLANK pin, for when it isn't given
+// to the driver. A high BLANK pin blanks the display, so the duty cycle is the time blanked.
+pub struct BlankPwm<P: SetDutyCycle> {
+    channel: P,
+}
+
+impl<P: SetDutyCycle> BlankPwm<P> {
+    pub fn new(channel: P) -> Self {
+        BlankPwm { channel }
+    }
+
+    // levels above BLANK_DIM_MAX are clamped
+    pub fn set_level(&mut self, level: u8) -> Result<(), P::Error> {
+        let blanked = BLANK_DIM_MAX - level.min(BLANK_DIM_MAX);
+        self.channel
+            .set_duty_cycle_fraction(blanked as u16, BLANK_DIM_MAX as u16)
+    }
+
+    pub fn release(self) -> P {
+        self.channel
+    }
+}
@@ -0,0 +1,198 @@
+use crate::format::{Align, FormatError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeLayout {
+    // the most detailed layout that fits, MM:SS for durations under an hour, then HH:MM:SS, then H:MM
+    #[default]
+    Auto,
+    HhMmSs,
+    MmSs,
+    HMm,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TimeFormat {
+    pub layout: TimeLayout,
+    pub align: Align,
+    // 12 hour time of day with an AM/PM suffix, shortened to A/P or dropped when there's no room
+    pub hour12: bool,
+    // hide the colons on odd ticks, e.g. for a tick every half second
+    pub blink_colon: bool,
+}
+
+// longest text is a duration of u32::MAX seconds, "1193046:28:15", or a time of day with " PM"
+pub(crate) const MAX_TIME_LEN: usize = 13;
+
+const SECONDS_PER_DAY: u32 = 24 * 60 * 60;
+
+// Formats a time of day in seconds since midnight into buf, e.g. "12:34:56" or "9:05PM".
+pub fn format_time(
+    buf: &mut [u8],
+    seconds: u32,
+    format: &TimeFormat,
+    tick: u32,
+) -> Result<(), FormatError> {
+    format_clock(buf, seconds % SECONDS_PER_DAY, format, false, tick)
+}
+
+// Formats an elapsed or remaining duration in seconds into buf, hours are not wrapped.
+pub fn format_duration(
+    buf: &mut [u8],
+    seconds: u32,
+    format: &TimeFormat,
+    tick: u32,
+) -> Result<(), FormatError> {
+    format_clock(buf, seconds, format, true, tick)
+}
+
+fn format_clock(
+    buf: &mut [u8],
+    seconds: u32,
+    format: &TimeFormat,
+    is_duration: bool,
+    tick: u32,
+) -> Result<(), FormatError> {
+    let mut text = [0; MAX_TIME_LEN];
+    let len = match time_text(&mut text, seconds, format, is_duration, buf.len(), false) {
+        Ok(len) => len,
+        Err(error) => {
+            // left blank like a number field that doesn't fit, rather than keeping old text
+            buf.fill(b' ');
+            return Err(error);
+        }
+    };
+    if !colon_visible(format, tick) {
+        text[..len]
+            .iter_mut()
+            .filter(|c| **c == b':')
+            .for_each(|c| *c = b' ');
+    }
+
+    let spaces = buf.len() - len;
+    let start = match format.align {
+        Align::Left => 0,
+        Align::Right => spaces,
+        Align::Center => spaces / 2,
+    };
+    buf.fill(b' ');
+    buf[start..start + len].copy_from_slice(&text[..len]);
+    Ok(())
+}
+
+pub(crate) fn colon_visible(format: &TimeFormat, tick: u32) -> bool {
+    !format.blink_colon || tick % 2 == 0
+}
+
+// Writes the most detailed variant of the layout that fits in max_cells, returning its length.
+// Narrow colons share a cell with the digit before them, so don't count towards the cells used.
+pub(crate) fn time_text(
+    text: &mut [u8; MAX_TIME_LEN],
+    seconds: u32,
+    format: &TimeFormat,
+    is_duration: bool,
+    max_cells: usize,
+    narrow_colons: bool,
+) -> Result<usize, FormatError> {
+    let layouts: &[TimeLayout] = match format.layout {
+        TimeLayout::Auto if is_duration && seconds < 60 * 60 => &[TimeLayout::MmSs],
+        TimeLayout::Auto => &[TimeLayout::HhMmSs, TimeLayout::HMm],
+        TimeLayout::HhMmSs => &[TimeLayout::HhMmSs],
+        TimeLayout::MmSs => &[TimeLayout::MmSs],
+        TimeLayout::HMm => &[TimeLayout::HMm],
+    };
+    let hour12 = format.hour12 && !is_duration;
+    let is_pm = seconds >= 12 * 60 * 60;
+    let suffixes: &[&[u8]] = match (hour12, is_pm) {
+        (false, _) => &[],
+        (true, false) => &[b" AM", b"AM", b"A"],
+        (true, true) => &[b" PM", b"PM", b"P"],
+    };
+
+    // dropping seconds is better than dropping AM/PM, so every layout is tried with a suffix first
+    let with_suffix = layouts
+        .iter()
+        .flat_map(|&layout| suffixes.iter().map(move |&suffix| (layout, suffix)));
+    let without_suffix = layouts.iter().map(|&layout| (layout, &b""[..]));
+    for (layout, suffix) in with_suffix.chain(without_suffix) {
+        let mut writer = TextWriter { text, len: 0 };
+        writer.push_fields(seconds, layout, hour12, is_duration);
+        writer.push_str(suffix);
+
+        let colons = if narrow_colons {
+            writer.text[..writer.len]
+                .iter()
+                .filter(|&&c| c == b':')
+                .count()
+        } else {
+            0
+        };
+        if writer.len - colons <= max_cells {
+            return Ok(writer.len);
+        }
+    }
+
+    Err(FormatError::ValueTooLong)
+}
+
+struct TextWriter<'a> {
+    text: &'a mut [u8; MAX_TIME_LEN],
+    len: usize,
+}
+
+impl TextWriter<'_> {
+    fn push_str(&mut self, s: &[u8]) {
+        self.text[self.len..self.len + s.len()].copy_from_slice(s);
+        self.len += s.len();
+    }
+
+    fn push_number(&mut self, mut value: u32, min_digits: usize) {
+        let mut digits = [b'0'; 10];
+        let mut count = 0;
+        while value > 0 || count < min_digits.max(1) {
+            digits[count] = b'0' + (value % 10) as u8;
+            value /= 10;
+            count += 1;
+        }
+        for &digit in digits[..count].iter().rev() {
+            self.push_str(&[digit]);
+        }
+    }
+
+    fn push_fields(&mut self, seconds: u32, layout: TimeLayout, hour12: bool, is_duration: bool) {
+        let mut hours = seconds / (60 * 60);
+        if hour12 {
+            hours = match hours % 12 {
+                0 => 12,
+                hours => hours,
+            };
+        }
+        // 24 hour times and durations keep a leading zero, 12 hour times don't
+        let hour_digits = if hour12 { 1 } else { 2 };
+
+        match layout {
+            TimeLayout::Auto | TimeLayout::HhMmSs => {
+                self.push_number(hours, hour_digits);
+                self.push_str(b":");
+                self.push_number(seconds / 60 % 60, 2);
+                self.push_str(b":");
+                self.push_number(seconds % 60, 2);
+            }
+            TimeLayout::MmSs => {
+                // durations count up minutes instead of wrapping them into hours
+                let minutes = if is_duration {
+                    seconds / 60
+                } else {
+                    seconds / 60 % 60
+                };
+                self.push_number(minutes, 2);
+                self.push_str(b":");
+                self.push_number(seconds % 60, 2);
+            }
+            TimeLayout::HMm => {
+                self.push_number(hours, 1);
+                self.push_str(b":");
+                self.push_number(seconds / 60 % 60, 2);
+            }
+        }
+    }
+}
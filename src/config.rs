@@ -0,0 +1,67 @@
+use crate::control_word::{ControlWord0, ControlWord1, DataOutMode, ExtOscPrescaler, Oscillator};
+use crate::PeakCurrent;
+
+const EXTERNAL_OSC_BIT: u8 = 0b0000_0100;
+
+// Display settings that can be read with config and applied with apply_config, e.g. for a
+// settings menu. Power states are left to set_power_state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayConfig {
+    pub brightness: u8,
+    pub peak_current: PeakCurrent,
+    pub oscillator: Oscillator,
+    pub ext_osc_prescaler: ExtOscPrescaler,
+    pub data_out_mode: DataOutMode,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        DisplayConfig {
+            brightness: ControlWord0::BRIGHTNESS_DEFAULT,
+            peak_current: PeakCurrent::default(),
+            oscillator: Oscillator::default(),
+            ext_osc_prescaler: ExtOscPrescaler::default(),
+            data_out_mode: DataOutMode::default(),
+        }
+    }
+}
+
+impl DisplayConfig {
+    // packs the settings into two bytes for storage, e.g. in EEPROM
+    pub fn to_bytes(&self) -> [u8; 2] {
+        let mut control_word_0 = ControlWord0::default();
+        control_word_0.set_brightness_bits(self.brightness);
+        control_word_0.set_peak_current_bits(self.peak_current);
+
+        let mut control_word_1 = ControlWord1::default();
+        control_word_1.set_data_out_mode_bit(self.data_out_mode);
+        control_word_1.set_ext_osc_prescaler_bit(self.ext_osc_prescaler);
+        let oscillator = match self.oscillator {
+            Oscillator::Internal => 0,
+            Oscillator::External => EXTERNAL_OSC_BIT,
+        };
+
+        [
+            control_word_0.bits()
+                & (ControlWord0::BRIGHTNESS_MASK | ControlWord0::PEAK_CURRENT_MASK),
+            (control_word_1.bits() & !ControlWord1::WORD_SELECT_BIT) | oscillator,
+        ]
+    }
+
+    // unused bits are ignored
+    pub fn from_bytes(bytes: [u8; 2]) -> Self {
+        let control_word_0 = ControlWord0::from_bits(bytes[0]);
+        let control_word_1 = ControlWord1::from_bits(bytes[1]);
+        DisplayConfig {
+            brightness: control_word_0.brightness_bits(),
+            peak_current: control_word_0.peak_current(),
+            oscillator: if bytes[1] & EXTERNAL_OSC_BIT == 0 {
+                Oscillator::Internal
+            } else {
+                Oscillator::External
+            },
+            ext_osc_prescaler: control_word_1.ext_osc_prescaler(),
+            data_out_mode: control_word_1.data_out_mode(),
+        }
+    }
+}
@@ -21,6 +21,23 @@ impl ControlWord0 {
         self.0 = (self.0 & !Self::WAKE_BIT) | mode as u8;
     }
 
+    pub fn from_bits(bits: u8) -> Self {
+        ControlWord0(bits & !ControlWord1::WORD_SELECT_BIT)
+    }
+
+    pub fn brightness_bits(&self) -> u8 {
+        self.0 & Self::BRIGHTNESS_MASK
+    }
+
+    pub fn peak_current(&self) -> PeakCurrent {
+        match self.0 & Self::PEAK_CURRENT_MASK {
+            0b0010_0000 => PeakCurrent::Max4_0Ma,
+            0b0001_0000 => PeakCurrent::Max6_4Ma,
+            0b0000_0000 => PeakCurrent::Max9_3Ma,
+            _ => PeakCurrent::Max12_8Ma,
+        }
+    }
+
     pub fn bits(&self) -> u8 {
         self.0
     }
@@ -36,6 +53,14 @@ impl Default for ControlWord0 {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Oscillator {
+    #[default]
+    Internal,
+    // clock from the OSC pin, selected with the OscSel pin
+    External,
+}
+
 #[derive(Default)]
 pub enum SleepMode {
     Sleep = 0b0000_0000,
@@ -68,6 +93,26 @@ impl ControlWord1 {
         self.0 = (self.0 & !Self::EXT_OSC_PRESCALER_BIT) | (bit as u8);
     }
 
+    pub fn from_bits(bits: u8) -> Self {
+        ControlWord1(bits | Self::WORD_SELECT_BIT)
+    }
+
+    pub fn data_out_mode(&self) -> DataOutMode {
+        if self.0 & Self::DATA_OUT_BIT == 0 {
+            DataOutMode::Serial
+        } else {
+            DataOutMode::Simultaneous
+        }
+    }
+
+    pub fn ext_osc_prescaler(&self) -> ExtOscPrescaler {
+        if self.0 & Self::EXT_OSC_PRESCALER_BIT == 0 {
+            ExtOscPrescaler::Direct
+        } else {
+            ExtOscPrescaler::Div8
+        }
+    }
+
     pub fn bits(&self) -> u8 {
         self.0
     }
@@ -93,5 +138,5 @@ pub enum DataOutMode {
 pub enum ExtOscPrescaler {
     #[default]
     Direct = 0b0000_0000,
-    Div8 = 0b0000_0001,
+    Div8 = 0b0000_0010,
 }
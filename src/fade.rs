@@ -0,0 +1,148 @@
+use crate::control_word::ControlWord0;
+use crate::luminance::LUMINANCE_MAX;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FadeScale {
+    // the 16 PWM brightness levels, leaving the peak current as it is
+    Brightness,
+    // 64 perceptually even levels combining the PWM brightness with the peak current
+    #[default]
+    Luminance,
+}
+
+impl FadeScale {
+    pub fn max_level(self) -> u8 {
+        match self {
+            FadeScale::Brightness => ControlWord0::BRIGHTNESS_MASK,
+            FadeScale::Luminance => LUMINANCE_MAX,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    // starts slow
+    EaseIn,
+    // ends slow
+    EaseOut,
+    // starts and ends slow, a smooth pulse when breathing
+    EaseInOut,
+}
+
+impl Easing {
+    // eases progress in 1/65536ths
+    fn apply(self, t: u32) -> u32 {
+        const ONE: u64 = 1 << 16;
+        let t = t as u64;
+        let eased = match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t / ONE,
+            Easing::EaseOut => ONE - (ONE - t) * (ONE - t) / ONE,
+            // smoothstep, 3t^2 - 2t^3
+            Easing::EaseInOut => t * t * (3 * ONE - 2 * t) / (ONE * ONE),
+        };
+        eased as u32
+    }
+}
+
+// Ramps the display brightness from one level to another over a number of ticks, or back and
+// forth forever when breathing. Apply it with tick_fade, e.g. every 20 ms.
+#[derive(Debug, Clone)]
+pub struct Fade {
+    scale: FadeScale,
+    from: u8,
+    to: u8,
+    step: u16,
+    steps: u16,
+    easing: Easing,
+    breathing: bool,
+    level: u8,
+    started: bool,
+}
+
+impl Fade {
+    // levels above the scale's max_level are clamped
+    pub fn new(scale: FadeScale, from: u8, to: u8, ticks: u16, easing: Easing) -> Self {
+        let from = from.min(scale.max_level());
+        Fade {
+            scale,
+            from,
+            to: to.min(scale.max_level()),
+            step: 0,
+            steps: ticks.max(1),
+            easing,
+            breathing: false,
+            level: from,
+            started: false,
+        }
+    }
+
+    // repeats low to high and back to low every period_ticks, e.g. for an alert
+    pub fn breathing(
+        scale: FadeScale,
+        low: u8,
+        high: u8,
+        period_ticks: u16,
+        easing: Easing,
+    ) -> Self {
+        let mut fade = Fade::new(scale, low, high, period_ticks.max(2), easing);
+        fade.breathing = true;
+        fade
+    }
+
+    pub fn scale(&self) -> FadeScale {
+        self.scale
+    }
+
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+
+    // breathing never finishes
+    pub fn is_finished(&self) -> bool {
+        !self.breathing && self.step >= self.steps
+    }
+
+    // advances one tick, returning the new level when it changed, the first tick always returns
+    // a level so the display doesn't stay at its previous brightness
+    pub fn tick(&mut self) -> Option<u8> {
+        if self.is_finished() {
+            return None;
+        }
+        self.step += 1;
+        if self.breathing && self.step >= self.steps {
+            self.step = 0;
+        }
+
+        let level = self.level_at(self.step);
+        if self.started && level == self.level {
+            return None;
+        }
+        self.started = true;
+        self.level = level;
+        Some(level)
+    }
+
+    fn level_at(&self, step: u16) -> u8 {
+        // breathing goes up in the first half of the period and down in the second
+        let (step, steps) = if self.breathing {
+            let half = self.steps / 2;
+            if step <= half {
+                (step, half)
+            } else {
+                (self.steps - step, self.steps - half)
+            }
+        } else {
+            (step, self.steps)
+        };
+
+        let progress = self
+            .easing
+            .apply(((step as u32) << 16) / steps.max(1) as u32)
+            .min(1 << 16) as i32;
+        let (from, to) = (self.from as i32, self.to as i32);
+        (from + ((to - from) * progress + (1 << 15)).div_euclid(1 << 16)) as u8
+    }
+}
@@ -0,0 +1,131 @@
+use crate::font5x7::{FONT5X7, FONT5X7_RANGE};
+use crate::CHAR_WIDTH;
+
+// Defines a font table from a single copy of its data, placed in PROGMEM for AVR builds and in
+// normal flash otherwise.
+macro_rules! font_table {
+    ($vis:vis static $name:ident: [u8; $len:expr] = $data:expr;) => {
+        #[cfg(feature = "avr-progmem")]
+        avr_progmem::progmem! {
+            $vis static progmem $name: [u8; $len] = $data;
+        }
+
+        #[cfg(not(feature = "avr-progmem"))]
+        $vis static $name: [u8; $len] = $data;
+    };
+}
+pub(crate) use font_table;
+
+// Copies the glyphs for first..=last out of a font table, updating the metadata for the new range.
+pub(crate) const fn font_subset<const N: usize>(font: &[u8], first: u8, last: u8) -> [u8; N] {
+    assert!(
+        first >= font[0] && last <= font[1],
+        "subset outside of font range"
+    );
+    assert!(N == (last - first) as usize * CHAR_WIDTH + 2 * CHAR_WIDTH);
+
+    let mut subset = [0u8; N];
+    subset[0] = first;
+    subset[1] = last;
+
+    let offset = (first - font[0]) as usize * CHAR_WIDTH;
+    let mut i = CHAR_WIDTH;
+    while i < N {
+        subset[i] = font[offset + i];
+        i += 1;
+    }
+    subset
+}
+
+pub(crate) const NARROW_DIGIT_WIDTH: usize = 3;
+
+// digits narrowed to 3 columns, leaving a blank column and then a column for a narrow '.', ':' or ','
+font_table! {
+    static NARROW_DIGITS: [u8; 10 * NARROW_DIGIT_WIDTH] = [
+        0x3E, 0x41, 0x3E, // 0
+        0x42, 0x7F, 0x40, // 1
+        0x71, 0x49, 0x46, // 2
+        0x41, 0x49, 0x36, // 3
+        0x0F, 0x08, 0x7F, // 4
+        0x4F, 0x49, 0x31, // 5
+        0x3E, 0x49, 0x31, // 6
+        0x01, 0x79, 0x07, // 7
+        0x36, 0x49, 0x36, // 8
+        0x46, 0x49, 0x3E, // 9
+    ];
+}
+
+// single column punctuation that can share a cell with a narrow digit
+pub(crate) fn narrow_punct_col(byte: u8) -> Option<u8> {
+    match byte {
+        b'.' => Some(0x40),
+        b',' => Some(0x60),
+        b':' => Some(0x14),
+        _ => None,
+    }
+}
+
+// number of punctuation characters print_dense merges into the cell of the digit before them
+pub(crate) fn dense_merge_count(bytes: &[u8]) -> usize {
+    let mut count = 0;
+    let mut i = 0;
+    while i + 1 < bytes.len() {
+        if bytes[i].is_ascii_digit() && narrow_punct_col(bytes[i + 1]).is_some() {
+            count += 1;
+            i += 2;
+        } else {
+            i += 1;
+        }
+    }
+    count
+}
+
+pub(crate) fn narrow_digit_byte(digit: u8, col: usize) -> u8 {
+    let index = digit as usize * NARROW_DIGIT_WIDTH + col;
+    #[cfg(feature = "avr-progmem")]
+    return NARROW_DIGITS.load_at(index);
+    #[cfg(not(feature = "avr-progmem"))]
+    NARROW_DIGITS[index]
+}
+
+pub(crate) fn font_byte(index: usize) -> u8 {
+    #[cfg(feature = "avr-progmem")]
+    return FONT5X7.load_at(index);
+    #[cfg(not(feature = "avr-progmem"))]
+    FONT5X7[index]
+}
+
+// bytes below the font's range end a line of text
+pub(crate) fn ends_text(byte: u8) -> bool {
+    byte < FONT5X7_RANGE.0
+}
+
+// column of a character in FONT5X7, characters outside of the font's range are left blank
+pub(crate) fn glyph_col(byte: u8, col: usize) -> u8 {
+    let (first, last) = FONT5X7_RANGE;
+    if byte < first || byte > last {
+        return 0x00;
+    }
+    font_byte((byte - first + 1) as usize * CHAR_WIDTH + col)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::font5x7::FONT5X7_DATA;
+
+    // run with and without the avr-progmem feature to check both storage backends
+    #[test]
+    fn font_bytes_match_source_data() {
+        let first = font_byte(0);
+        let last = font_byte(1);
+        let offset = (first - FONT5X7_DATA[0]) as usize * CHAR_WIDTH;
+        for index in CHAR_WIDTH..(last - first) as usize * CHAR_WIDTH + 2 * CHAR_WIDTH {
+            assert_eq!(
+                font_byte(index),
+                FONT5X7_DATA[offset + index],
+                "font byte {index}"
+            );
+        }
+    }
+}
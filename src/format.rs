@@ -0,0 +1,1089 @@
+use crate::font::dense_merge_count;
+use core::ops::DivAssign;
+#[cfg(feature = "print_float")]
+use num_traits::float::FloatCore;
+use num_traits::{checked_pow, PrimInt, ToPrimitive};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatError {
+    ValueTooLong,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Align {
+    Left,
+    #[default]
+    Right,
+    Center,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Padding {
+    #[default]
+    Space,
+    // zeros are placed between the sign/prefix and the digits, alignment is ignored
+    Zero,
+}
+
+// negative values are shown as a sign and magnitude in every radix, e.g. -1 is -0x1 not 0xFFFFFFFF
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Radix {
+    Binary,
+    Octal,
+    #[default]
+    Decimal,
+    HexLower,
+    HexUpper,
+}
+
+impl Radix {
+    fn base(self) -> u8 {
+        match self {
+            Radix::Binary => 2,
+            Radix::Octal => 8,
+            Radix::Decimal => 10,
+            Radix::HexLower | Radix::HexUpper => 16,
+        }
+    }
+
+    fn prefix(self) -> &'static [u8] {
+        match self {
+            Radix::Binary => b"0b",
+            Radix::Octal => b"0o",
+            Radix::Decimal => b"",
+            Radix::HexLower | Radix::HexUpper => b"0x",
+        }
+    }
+
+    fn digit(self, value: u8) -> u8 {
+        match value {
+            0..=9 => b'0' + value,
+            _ if self == Radix::HexUpper => b'A' + value - 10,
+            _ => b'a' + value - 10,
+        }
+    }
+}
+
+// what format_float does when a value doesn't fit the field, each policy other than Error first
+// drops fraction digits one at a time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Overflow {
+    #[default]
+    Error,
+    ReducePrecision,
+    // exponent notation, e.g. 1.2E6
+    Exponent,
+    // the largest value that fits with a '>' or '<' in front, e.g. ">999" or "<-99"
+    Saturate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Grouping {
+    // number of digits per group, counted from the least significant digit
+    pub size: u8,
+    pub separator: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NumberFormat {
+    pub align: Align,
+    pub padding: Padding,
+    // field width in characters, defaults to the whole display or buffer
+    pub width: Option<usize>,
+    // show a '+' for positive values
+    pub force_sign: bool,
+    // floats are always formatted in decimal
+    pub radix: Radix,
+    // show the radix prefix (0b, 0o, 0x) for non-decimal values
+    pub prefix: bool,
+    // separate integer digits into groups, e.g. 1,000,000 or 1010_0101
+    pub grouping: Option<Grouping>,
+    pub overflow: Overflow,
+}
+
+// Formats an integer into the start of buf, e.g. into a sub-field of a line for print_ascii_bytes.
+pub fn format_int<T>(buf: &mut [u8], value: T, format: &NumberFormat) -> Result<(), FormatError>
+where
+    T: Copy + ToPrimitive,
+{
+    let result = write_int(buf, value, format);
+    blank_field_on_error(buf, format, result)
+}
+
+pub(crate) fn write_int<T>(
+    buf: &mut [u8],
+    value: T,
+    format: &NumberFormat,
+) -> Result<(), FormatError>
+where
+    T: Copy + ToPrimitive,
+{
+    // avoid using 64-bit arithmetic for values that fit in 32 bits
+    if value.to_i32().is_some() || value.to_u32().is_some() {
+        format_int_32bit(buf, value, format)
+    } else {
+        format_int_64bit(buf, value, format)
+    }
+}
+
+fn format_int_32bit<T>(buf: &mut [u8], value: T, format: &NumberFormat) -> Result<(), FormatError>
+where
+    T: Copy + ToPrimitive,
+{
+    let (is_negative, magnitude) = if let Some(signed_val) = value.to_i32() {
+        (signed_val < 0, signed_val.unsigned_abs())
+    } else if let Some(unsigned_val) = value.to_u32() {
+        (false, unsigned_val)
+    } else {
+        return Err(FormatError::ValueTooLong);
+    };
+
+    let mut writer = FieldWriter::new(buf, format)?;
+    writer.push_digits(magnitude, 0)?;
+    writer.finish(is_negative, format.radix)
+}
+
+fn format_int_64bit<T>(buf: &mut [u8], value: T, format: &NumberFormat) -> Result<(), FormatError>
+where
+    T: Copy + ToPrimitive,
+{
+    let (is_negative, magnitude) = if let Some(signed_val) = value.to_i64() {
+        (signed_val < 0, signed_val.unsigned_abs())
+    } else if let Some(unsigned_val) = value.to_u64() {
+        (false, unsigned_val)
+    } else {
+        return Err(FormatError::ValueTooLong);
+    };
+
+    let mut writer = FieldWriter::new(buf, format)?;
+    writer.push_digits(magnitude, 0)?;
+    writer.finish(is_negative, format.radix)
+}
+
+// Formats a scaled integer as a decimal, e.g. 2345 with scale 2 is 23.45, rounding or padding
+// the fraction to `precision` digits.
+pub fn format_fixed<T>(
+    buf: &mut [u8],
+    value: T,
+    scale: u8,
+    precision: u8,
+    format: &NumberFormat,
+) -> Result<(), FormatError>
+where
+    T: Copy + ToPrimitive,
+{
+    let result = write_fixed(buf, value, scale, precision, format);
+    blank_field_on_error(buf, format, result)
+}
+
+pub(crate) fn write_fixed<T>(
+    buf: &mut [u8],
+    value: T,
+    scale: u8,
+    precision: u8,
+    format: &NumberFormat,
+) -> Result<(), FormatError>
+where
+    T: Copy + ToPrimitive,
+{
+    // avoid using 64-bit arithmetic for values that fit in 32 bits, unless rescaling overflows them
+    if value.to_i32().is_some() || value.to_u32().is_some() {
+        format_fixed_32bit(buf, value, scale, precision, format)
+            .or_else(|_| format_fixed_64bit(buf, value, scale, precision, format))
+    } else {
+        format_fixed_64bit(buf, value, scale, precision, format)
+    }
+}
+
+fn format_fixed_32bit<T>(
+    buf: &mut [u8],
+    value: T,
+    scale: u8,
+    precision: u8,
+    format: &NumberFormat,
+) -> Result<(), FormatError>
+where
+    T: Copy + ToPrimitive,
+{
+    let (is_negative, magnitude) = if let Some(signed_val) = value.to_i32() {
+        (signed_val < 0, signed_val.unsigned_abs())
+    } else if let Some(unsigned_val) = value.to_u32() {
+        (false, unsigned_val)
+    } else {
+        return Err(FormatError::ValueTooLong);
+    };
+    let magnitude = rescale(magnitude, scale, precision)?;
+
+    let format = NumberFormat {
+        radix: Radix::Decimal,
+        ..*format
+    };
+    let mut writer = FieldWriter::new(buf, &format)?;
+    writer.push_digits(magnitude, precision)?;
+    writer.finish(is_negative && magnitude != 0, Radix::Decimal)
+}
+
+fn format_fixed_64bit<T>(
+    buf: &mut [u8],
+    value: T,
+    scale: u8,
+    precision: u8,
+    format: &NumberFormat,
+) -> Result<(), FormatError>
+where
+    T: Copy + ToPrimitive,
+{
+    let (is_negative, magnitude) = if let Some(signed_val) = value.to_i64() {
+        (signed_val < 0, signed_val.unsigned_abs())
+    } else if let Some(unsigned_val) = value.to_u64() {
+        (false, unsigned_val)
+    } else {
+        return Err(FormatError::ValueTooLong);
+    };
+    let magnitude = rescale(magnitude, scale, precision)?;
+
+    let format = NumberFormat {
+        radix: Radix::Decimal,
+        ..*format
+    };
+    let mut writer = FieldWriter::new(buf, &format)?;
+    writer.push_digits(magnitude, precision)?;
+    writer.finish(is_negative && magnitude != 0, Radix::Decimal)
+}
+
+// converts a magnitude with `scale` fraction digits to one with `precision` fraction digits
+fn rescale<U: PrimInt>(magnitude: U, scale: u8, precision: u8) -> Result<U, FormatError> {
+    let ten = U::from(10).ok_or(FormatError::ValueTooLong)?;
+
+    if precision >= scale {
+        let factor =
+            checked_pow(ten, (precision - scale) as usize).ok_or(FormatError::ValueTooLong)?;
+        return magnitude
+            .checked_mul(&factor)
+            .ok_or(FormatError::ValueTooLong);
+    }
+
+    // round half away from zero, a divisor too large to represent rounds any magnitude to zero
+    let Some(divisor) = checked_pow(ten, (scale - precision) as usize) else {
+        return Ok(U::zero());
+    };
+    let quotient = magnitude / divisor;
+    let remainder = magnitude % divisor;
+    if remainder >= divisor - remainder {
+        Ok(quotient + U::one())
+    } else {
+        Ok(quotient)
+    }
+}
+
+#[cfg(feature = "print_float")]
+pub fn format_float<T>(
+    buf: &mut [u8],
+    value: T,
+    precision: u8,
+    format: &NumberFormat,
+) -> Result<(), FormatError>
+where
+    T: Copy + ToPrimitive,
+{
+    let result = write_float(buf, value, precision, format);
+    blank_field_on_error(buf, format, result)
+}
+
+#[cfg(feature = "print_float")]
+fn write_float<T>(
+    buf: &mut [u8],
+    value: T,
+    precision: u8,
+    format: &NumberFormat,
+) -> Result<(), FormatError>
+where
+    T: Copy + ToPrimitive,
+{
+    let float_val = value.to_f32().ok_or(FormatError::ValueTooLong)?;
+    let format = NumberFormat {
+        radix: Radix::Decimal,
+        ..*format
+    };
+
+    if let Some(result) = format_special(buf, float_val, &format) {
+        return result;
+    }
+
+    let mut precision = precision;
+    loop {
+        let result = format_float_digits(buf, float_val, precision, &format);
+        if result.is_ok() || format.overflow == Overflow::Error {
+            return result;
+        }
+        if precision == 0 {
+            break;
+        }
+        precision -= 1;
+    }
+
+    let is_negative = float_val.is_sign_negative();
+    match format.overflow {
+        Overflow::Error | Overflow::ReducePrecision => Err(FormatError::ValueTooLong),
+        Overflow::Exponent => {
+            let (mantissa, exponent) = decompose_float(float_val);
+            let msd_exp = exponent + decimal_digits(mantissa) as i16 - 1;
+            format_scientific(buf, is_negative, mantissa, msd_exp, b"", &format)
+        }
+        Overflow::Saturate => format_saturated(buf, is_negative, &format),
+    }
+}
+
+#[cfg(feature = "print_float")]
+fn format_float_digits(
+    buf: &mut [u8],
+    float_val: f32,
+    precision: u8,
+    format: &NumberFormat,
+) -> Result<(), FormatError> {
+    // scale number to integer value for formatting
+    let mut scale_factor = 1.0f32;
+    for _ in 0..precision {
+        scale_factor *= 10.0;
+    }
+    let rounded_val = FloatCore::abs(float_val) * scale_factor + 0.5;
+    if rounded_val >= u32::MAX as f32 {
+        return Err(FormatError::ValueTooLong);
+    }
+
+    let mut writer = FieldWriter::new(buf, format)?;
+    writer.push_digits(rounded_val as u32, precision)?;
+    writer.finish(float_val.is_sign_negative(), Radix::Decimal)
+}
+
+// all nines behind a '>' or '<' marker, right aligned since the marker takes the only spare character
+#[cfg(feature = "print_float")]
+fn format_saturated(
+    buf: &mut [u8],
+    is_negative: bool,
+    format: &NumberFormat,
+) -> Result<(), FormatError> {
+    let width = format.width.unwrap_or(buf.len());
+    let sign_len = (is_negative || format.force_sign) as usize;
+    let nines = width
+        .checked_sub(sign_len + 1)
+        .filter(|&nines| nines > 0)
+        .ok_or(FormatError::ValueTooLong)?;
+
+    let format = NumberFormat {
+        align: Align::Right,
+        padding: Padding::Space,
+        grouping: None,
+        ..*format
+    };
+    let mut writer = FieldWriter::new(buf, &format)?;
+    for _ in 0..nines {
+        writer.push(b'9')?;
+    }
+    writer.finish(is_negative, Radix::Decimal)?;
+    buf[0] = if is_negative { b'<' } else { b'>' };
+    Ok(())
+}
+
+// NaN and infinity, None for finite values
+#[cfg(feature = "print_float")]
+fn format_special(
+    buf: &mut [u8],
+    float_val: f32,
+    format: &NumberFormat,
+) -> Option<Result<(), FormatError>> {
+    let special_format = NumberFormat {
+        padding: Padding::Space,
+        ..*format
+    };
+    let (text, is_negative, force_sign) = if float_val.is_nan() {
+        (b"NaN", false, false)
+    } else if float_val.is_infinite() {
+        (b"Inf", float_val.is_sign_negative(), true)
+    } else {
+        return None;
+    };
+
+    let format = NumberFormat {
+        force_sign,
+        ..special_format
+    };
+    Some(FieldWriter::new(buf, &format).and_then(|mut writer| {
+        writer.push_str(text)?;
+        writer.finish(is_negative, Radix::Decimal)
+    }))
+}
+
+// SI prefixes from pico to giga in steps of 10^3, 0x0C is the mu glyph in FONT5X7
+const SI_PREFIXES: [&[u8]; 8] = [b"p", b"n", b"\x0C", b"m", b"", b"k", b"M", b"G"];
+const SI_MIN_EXP: i16 = -12;
+const SI_MAX_EXP: i16 = 9;
+
+// Formats value * 10^exponent with an SI prefix and unit, showing as many significant digits as
+// fit, e.g. 4700 with exponent -9 and unit b"F" is "4.700\x0CF" (4.7 microfarads). Values outside
+// the prefix range or too wide for the field fall back to scientific notation, e.g. "1.2E-15F".
+pub fn format_si<T>(
+    buf: &mut [u8],
+    value: T,
+    exponent: i8,
+    unit: &[u8],
+    format: &NumberFormat,
+) -> Result<(), FormatError>
+where
+    T: Copy + ToPrimitive,
+{
+    let exponent = exponent as i16;
+    // avoid using 64-bit arithmetic for values that fit in 32 bits
+    let result = if let Some(signed_val) = value.to_i32() {
+        format_si_digits(
+            buf,
+            signed_val < 0,
+            signed_val.unsigned_abs(),
+            exponent,
+            unit,
+            format,
+        )
+    } else if let Some(unsigned_val) = value.to_u32() {
+        format_si_digits(buf, false, unsigned_val, exponent, unit, format)
+    } else if let Some(signed_val) = value.to_i64() {
+        format_si_digits(
+            buf,
+            signed_val < 0,
+            signed_val.unsigned_abs(),
+            exponent,
+            unit,
+            format,
+        )
+    } else if let Some(unsigned_val) = value.to_u64() {
+        format_si_digits(buf, false, unsigned_val, exponent, unit, format)
+    } else {
+        Err(FormatError::ValueTooLong)
+    };
+    blank_field_on_error(buf, format, result)
+}
+
+#[cfg(feature = "print_float")]
+pub fn format_si_float<T>(
+    buf: &mut [u8],
+    value: T,
+    unit: &[u8],
+    format: &NumberFormat,
+) -> Result<(), FormatError>
+where
+    T: Copy + ToPrimitive,
+{
+    let result = write_si_float(buf, value, unit, format);
+    blank_field_on_error(buf, format, result)
+}
+
+#[cfg(feature = "print_float")]
+fn write_si_float<T>(
+    buf: &mut [u8],
+    value: T,
+    unit: &[u8],
+    format: &NumberFormat,
+) -> Result<(), FormatError>
+where
+    T: Copy + ToPrimitive,
+{
+    let float_val = value.to_f32().ok_or(FormatError::ValueTooLong)?;
+    if let Some(result) = format_special(buf, float_val, format) {
+        return result;
+    }
+
+    let (mantissa, exponent) = decompose_float(float_val);
+    format_si_digits(
+        buf,
+        float_val.is_sign_negative() && mantissa != 0,
+        mantissa,
+        exponent,
+        unit,
+        format,
+    )
+}
+
+// splits a finite float into a mantissa of up to 6 digits and a power of ten, dropping trailing zeros
+#[cfg(feature = "print_float")]
+fn decompose_float(float_val: f32) -> (u32, i16) {
+    let mut magnitude = FloatCore::abs(float_val);
+    if magnitude == 0.0 {
+        return (0, 0);
+    }
+
+    let mut exponent = 0i16;
+    while magnitude >= 1e6 {
+        magnitude /= 10.0;
+        exponent += 1;
+    }
+    while magnitude < 1e5 {
+        magnitude *= 10.0;
+        exponent -= 1;
+    }
+    let mut mantissa = (magnitude + 0.5) as u32;
+    while mantissa % 10 == 0 {
+        mantissa /= 10;
+        exponent += 1;
+    }
+    (mantissa, exponent)
+}
+
+fn format_si_digits<U>(
+    buf: &mut [u8],
+    is_negative: bool,
+    mut magnitude: U,
+    exponent: i16,
+    unit: &[u8],
+    format: &NumberFormat,
+) -> Result<(), FormatError>
+where
+    U: PrimInt + DivAssign + From<u8>,
+{
+    let format = NumberFormat {
+        radix: Radix::Decimal,
+        prefix: false,
+        grouping: None,
+        ..*format
+    };
+    let width = format.width.unwrap_or(buf.len());
+    let ten: U = 10.into();
+
+    if magnitude.is_zero() {
+        let mut writer = FieldWriter::new(buf, &format)?;
+        writer.push_str(unit)?;
+        writer.push_digits(magnitude, 0)?;
+        return writer.finish(false, Radix::Decimal);
+    }
+
+    let sign_len = (is_negative || format.force_sign) as usize;
+    let max_digits = decimal_digits(U::max_value()) - 1;
+    let digits = decimal_digits(magnitude);
+    // power of ten of the most significant digit
+    let mut msd_exp = exponent + digits as i16 - 1;
+
+    loop {
+        let prefix_exp = msd_exp.div_euclid(3) * 3;
+        if !(SI_MIN_EXP..=SI_MAX_EXP).contains(&prefix_exp) {
+            break;
+        }
+        let prefix = SI_PREFIXES[((prefix_exp - SI_MIN_EXP) / 3) as usize];
+        let int_digits = (msd_exp - prefix_exp + 1) as usize;
+        let Some(available) = width.checked_sub(sign_len + prefix.len() + unit.len()) else {
+            break;
+        };
+        if available < int_digits {
+            break;
+        }
+
+        // fill the field, but don't show more fraction digits than the value has
+        let precision = available
+            .saturating_sub(int_digits + 1)
+            .min(digits.saturating_sub(int_digits))
+            .min(max_digits - int_digits);
+        let Some(mantissa) = round_significant(magnitude, digits, int_digits + precision)? else {
+            // rounding carried into another digit, e.g. 999.7 to 1000
+            magnitude = checked_pow(ten, digits - 1).ok_or(FormatError::ValueTooLong)?;
+            msd_exp += 1;
+            continue;
+        };
+
+        let mut writer = FieldWriter::new(buf, &format)?;
+        writer.push_str(unit)?;
+        writer.push_str(prefix)?;
+        writer.push_digits(mantissa, precision as u8)?;
+        return writer.finish(is_negative, Radix::Decimal);
+    }
+
+    format_scientific(buf, is_negative, magnitude, msd_exp, unit, &format)
+}
+
+// scientific notation with a single integer digit and as many fraction digits as fit, e.g. 1.2E-5,
+// msd_exp is the power of ten of the most significant digit of a non-zero magnitude
+fn format_scientific<U>(
+    buf: &mut [u8],
+    is_negative: bool,
+    mut magnitude: U,
+    mut msd_exp: i16,
+    unit: &[u8],
+    format: &NumberFormat,
+) -> Result<(), FormatError>
+where
+    U: PrimInt + DivAssign + From<u8>,
+{
+    let width = format.width.unwrap_or(buf.len());
+    let sign_len = (is_negative || format.force_sign) as usize;
+    let max_digits = decimal_digits(U::max_value()) - 1;
+    let digits = decimal_digits(magnitude);
+    let ten: U = 10.into();
+
+    loop {
+        let exp_len = decimal_digits(msd_exp.unsigned_abs()) + (msd_exp < 0) as usize;
+        let available = width
+            .checked_sub(sign_len + unit.len() + 1 + exp_len)
+            .filter(|&available| available > 0)
+            .ok_or(FormatError::ValueTooLong)?;
+
+        let precision = available
+            .saturating_sub(2)
+            .min(digits - 1)
+            .min(max_digits - 1);
+        let Some(mantissa) = round_significant(magnitude, digits, precision + 1)? else {
+            magnitude = checked_pow(ten, digits - 1).ok_or(FormatError::ValueTooLong)?;
+            msd_exp += 1;
+            continue;
+        };
+
+        let mut writer = FieldWriter::new(buf, format)?;
+        writer.push_str(unit)?;
+        let mut exp = msd_exp.unsigned_abs();
+        loop {
+            writer.push(b'0' + (exp % 10) as u8)?;
+            exp /= 10;
+            if exp == 0 {
+                break;
+            }
+        }
+        if msd_exp < 0 {
+            writer.push(b'-')?;
+        }
+        writer.push(b'E')?;
+        writer.push_digits(mantissa, precision as u8)?;
+        return writer.finish(is_negative, Radix::Decimal);
+    }
+}
+
+fn decimal_digits<U: PrimInt>(mut value: U) -> usize {
+    let ten = U::from(10).unwrap_or(U::max_value());
+    let mut digits = 1;
+    while value >= ten {
+        value = value / ten;
+        digits += 1;
+    }
+    digits
+}
+
+// rounds a value with `digits` significant digits to `keep` digits, None if rounding carries
+// into an extra digit
+fn round_significant<U: PrimInt>(
+    magnitude: U,
+    digits: usize,
+    keep: usize,
+) -> Result<Option<U>, FormatError> {
+    let rounded = rescale(magnitude, digits as u8, keep as u8)?;
+    let limit = checked_pow(U::from(10).ok_or(FormatError::ValueTooLong)?, keep)
+        .ok_or(FormatError::ValueTooLong)?;
+    Ok((rounded < limit).then_some(rounded))
+}
+
+// Formats a number for print_dense on `cells` characters, where punctuation after a digit
+// shares its cell, returning the length of the text written to the start of buf.
+pub(crate) fn format_dense<F>(
+    buf: &mut [u8],
+    cells: usize,
+    format: &NumberFormat,
+    write: F,
+) -> Result<usize, FormatError>
+where
+    F: Fn(&mut [u8], &NumberFormat) -> Result<(), FormatError>,
+{
+    // merged punctuation makes the text longer than the display, so format once to find how
+    // many characters are merged and again with the field widened to match
+    let measure_format = NumberFormat {
+        align: Align::Right,
+        padding: Padding::Space,
+        width: None,
+        ..*format
+    };
+    write(buf, &measure_format)?;
+
+    // an overflow policy can change the text with the field width, e.g. switching to an exponent
+    // or dropping the decimal point, so retry until the text has the merges its field was sized
+    // for, settling for a field whose text merges more if that never happens
+    let mut merged = dense_merge_count(buf);
+    let mut fits = None;
+    for _ in 0..=cells {
+        let width = write_dense_field(buf, cells, merged, format, &write)?;
+        let text_merged = dense_merge_count(&buf[..width]);
+        if text_merged == merged {
+            return Ok(width);
+        }
+        if text_merged > merged {
+            fits = Some(merged);
+        }
+        merged = text_merged;
+    }
+
+    // the extra merges leave cells free, so pad by cells to keep the alignment
+    let merged = fits.ok_or(FormatError::ValueTooLong)?;
+    let width = write_dense_field(buf, cells, merged, format, &write)?;
+    let pad = dense_merge_count(&buf[..width]) - merged;
+    let len = width + pad;
+    if len > buf.len() {
+        return Err(FormatError::ValueTooLong);
+    }
+    let start = match format.align {
+        Align::Left => 0,
+        Align::Right => pad,
+        Align::Center => pad / 2,
+    };
+    buf.copy_within(..width, start);
+    buf[..start].fill(b' ');
+    buf[start + width..len].fill(b' ');
+    Ok(len)
+}
+
+fn write_dense_field<F>(
+    buf: &mut [u8],
+    cells: usize,
+    merged: usize,
+    format: &NumberFormat,
+    write: &F,
+) -> Result<usize, FormatError>
+where
+    F: Fn(&mut [u8], &NumberFormat) -> Result<(), FormatError>,
+{
+    let width = format.width.unwrap_or(cells) + merged;
+    let dense_format = NumberFormat {
+        width: Some(width),
+        ..*format
+    };
+    buf.fill(b' ');
+    write(buf, &dense_format)?;
+    Ok(width)
+}
+
+// a field that failed to format is left blank rather than holding part of the number, so a
+// sub-field of a line doesn't show a misleading value
+fn blank_field_on_error(
+    buf: &mut [u8],
+    format: &NumberFormat,
+    result: Result<(), FormatError>,
+) -> Result<(), FormatError> {
+    if result.is_err() {
+        if let Some(field) = buf.get_mut(..format.width.unwrap_or(buf.len())) {
+            field.fill(b' ');
+        }
+    }
+    result
+}
+
+// Writes a number into a field from the least significant character, then aligns it.
+struct FieldWriter<'a> {
+    field: &'a mut [u8],
+    pos: usize,
+    format: NumberFormat,
+    integer_digits: usize,
+}
+
+impl<'a> FieldWriter<'a> {
+    fn new(buf: &'a mut [u8], format: &NumberFormat) -> Result<Self, FormatError> {
+        let width = format.width.unwrap_or(buf.len());
+        if width > buf.len() {
+            return Err(FormatError::ValueTooLong);
+        }
+
+        Ok(FieldWriter {
+            field: &mut buf[..width],
+            pos: width,
+            format: *format,
+            integer_digits: 0,
+        })
+    }
+
+    fn push(&mut self, c: u8) -> Result<(), FormatError> {
+        if self.pos == 0 {
+            return Err(FormatError::ValueTooLong);
+        }
+        self.pos -= 1;
+        self.field[self.pos] = c;
+        Ok(())
+    }
+
+    // pushed in display order
+    fn push_str(&mut self, s: &[u8]) -> Result<(), FormatError> {
+        for &c in s.iter().rev() {
+            self.push(c)?;
+        }
+        Ok(())
+    }
+
+    fn next_digit_grouped(&self) -> bool {
+        match self.format.grouping {
+            Some(grouping) if grouping.size > 0 => {
+                self.integer_digits > 0 && self.integer_digits % grouping.size as usize == 0
+            }
+            _ => false,
+        }
+    }
+
+    fn push_integer_digit(&mut self, c: u8) -> Result<(), FormatError> {
+        if let (true, Some(grouping)) = (self.next_digit_grouped(), self.format.grouping) {
+            self.push(grouping.separator)?;
+        }
+        self.push(c)?;
+        self.integer_digits += 1;
+        Ok(())
+    }
+
+    // digits of an unsigned value, with a decimal point before the last `precision` digits
+    fn push_digits<U>(&mut self, mut value: U, precision: u8) -> Result<(), FormatError>
+    where
+        U: Copy + PartialOrd + core::ops::DivAssign + core::ops::Rem<Output = U> + From<u8>,
+        U: ToPrimitive,
+    {
+        let radix = self.format.radix;
+        let base = U::from(radix.base());
+        let zero = U::from(0);
+
+        for _ in 0..precision {
+            self.push(radix.digit((value % base).to_u8().unwrap_or(0)))?;
+            value /= base;
+        }
+
+        if precision > 0 {
+            self.push(b'.')?;
+        }
+
+        // always at least one integer digit
+        loop {
+            self.push_integer_digit(radix.digit((value % base).to_u8().unwrap_or(0)))?;
+            value /= base;
+            if value <= zero {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    // adds zero padding, sign and prefix, then aligns the number within the field
+    fn finish(mut self, is_negative: bool, radix: Radix) -> Result<(), FormatError> {
+        let sign = if is_negative {
+            Some(b'-')
+        } else if self.format.force_sign {
+            Some(b'+')
+        } else {
+            None
+        };
+        let prefix = if self.format.prefix {
+            radix.prefix()
+        } else {
+            b""
+        };
+        let lead_len = sign.is_some() as usize + prefix.len();
+
+        if self.format.padding == Padding::Zero {
+            while self.pos > lead_len {
+                // don't leave a dangling separator in front of the digits
+                if self.next_digit_grouped() && self.pos - lead_len < 2 {
+                    break;
+                }
+                self.push_integer_digit(b'0')?;
+            }
+        }
+
+        for &c in prefix.iter().rev() {
+            self.push(c)?;
+        }
+        if let Some(sign) = sign {
+            self.push(sign)?;
+        }
+
+        let spaces = self.pos;
+        self.field[..spaces].fill(b' ');
+        match (self.format.padding, self.format.align) {
+            (Padding::Zero, _) | (_, Align::Right) => {}
+            (_, Align::Left) => self.field.rotate_left(spaces),
+            (_, Align::Center) => self.field.rotate_left(spaces - spaces / 2),
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn formatted<const N: usize>(
+        write: impl Fn(&mut [u8], &NumberFormat) -> Result<(), FormatError>,
+        format: NumberFormat,
+    ) -> Result<[u8; N], FormatError> {
+        let mut buf = [b'#'; N];
+        write(&mut buf, &format).map(|_| buf)
+    }
+
+    #[test]
+    fn fixed_rounding_carries_into_integer_digits() {
+        let text = formatted::<6>(
+            |buf, f| format_fixed(buf, 9995, 3, 2, f),
+            Default::default(),
+        );
+        assert_eq!(&text.unwrap(), b" 10.00");
+        let text = formatted::<6>(
+            |buf, f| format_fixed(buf, -9995, 3, 2, f),
+            Default::default(),
+        );
+        assert_eq!(&text.unwrap(), b"-10.00");
+    }
+
+    #[test]
+    fn int_extremes() {
+        let text = formatted::<20>(|buf, f| format_int(buf, i64::MIN, f), Default::default());
+        assert_eq!(&text.unwrap(), b"-9223372036854775808");
+        let text = formatted::<20>(|buf, f| format_int(buf, u64::MAX, f), Default::default());
+        assert_eq!(&text.unwrap(), b"18446744073709551615");
+        let text = formatted::<11>(|buf, f| format_int(buf, i32::MIN, f), Default::default());
+        assert_eq!(&text.unwrap(), b"-2147483648");
+    }
+
+    #[test]
+    fn wide_values_in_narrow_fields() {
+        let hex = NumberFormat {
+            radix: Radix::HexUpper,
+            ..Default::default()
+        };
+        let text = formatted::<9>(|buf, f| format_int(buf, 0xF_FFFF_FFFFu64, f), hex);
+        assert_eq!(&text.unwrap(), b"FFFFFFFFF");
+        let text = formatted::<10>(
+            |buf, f| format_fixed(buf, 5_000_000_000u64, 0, 0, f),
+            Default::default(),
+        );
+        assert_eq!(&text.unwrap(), b"5000000000");
+        let text = formatted::<11>(
+            |buf, f| format_fixed(buf, 5_000_000, 0, 3, f),
+            Default::default(),
+        );
+        assert_eq!(&text.unwrap(), b"5000000.000");
+    }
+
+    #[test]
+    fn negative_non_decimal_is_sign_magnitude() {
+        let hex = NumberFormat {
+            radix: Radix::HexLower,
+            prefix: true,
+            ..Default::default()
+        };
+        let text = formatted::<4>(|buf, f| format_int(buf, -1, f), hex);
+        assert_eq!(&text.unwrap(), b"-0x1");
+    }
+
+    #[test]
+    fn zero_padding_with_grouping_and_sign() {
+        let format = NumberFormat {
+            padding: Padding::Zero,
+            force_sign: true,
+            grouping: Some(Grouping {
+                size: 3,
+                separator: b',',
+            }),
+            ..Default::default()
+        };
+        let text = formatted::<8>(|buf, f| format_int(buf, 1234, f), format);
+        assert_eq!(&text.unwrap(), b"+001,234");
+        let text = formatted::<8>(|buf, f| format_int(buf, -1234, f), format);
+        assert_eq!(&text.unwrap(), b"-001,234");
+    }
+
+    #[test]
+    fn too_long_leaves_field_blank() {
+        let format = NumberFormat {
+            width: Some(4),
+            ..Default::default()
+        };
+        let text = formatted::<6>(|buf, f| format_int(buf, 12345, f), format);
+        assert_eq!(text, Err(FormatError::ValueTooLong));
+        let mut buf = [b'#'; 6];
+        assert!(format_int(&mut buf, 12345, &format).is_err());
+        assert_eq!(&buf, b"    ##");
+    }
+
+    #[test]
+    fn si_prefix_boundaries() {
+        let si = |value: i64, exponent: i8| {
+            formatted::<8>(
+                |buf, f| format_si(buf, value, exponent, b"V", f),
+                Default::default(),
+            )
+            .unwrap()
+        };
+        assert_eq!(&si(999, 0), b"    999V");
+        assert_eq!(&si(1000, 0), b" 1.000kV");
+        assert_eq!(&si(999_950, 0), b"999.95kV");
+        // rounding to the digits that fit carries into the next prefix
+        let text = formatted::<6>(
+            |buf, f| format_si(buf, 999_960, 0, b"V", f),
+            Default::default(),
+        );
+        assert_eq!(&text.unwrap(), b"1.00MV");
+        assert_eq!(&si(1, -12), b"     1pV");
+        assert_eq!(&si(999, 9), b"   999GV");
+        // outside of pico to giga
+        assert_eq!(&si(1, -15), b"  1E-15V");
+        assert_eq!(&si(1000, 9), b"1.00E12V");
+    }
+
+    #[test]
+    fn dense_field_widened_by_merges() {
+        let mut buf = [b' '; 16];
+        let len = format_dense(&mut buf, 8, &NumberFormat::default(), |buf, format| {
+            format_fixed(buf, 12345, 2, 2, format)
+        });
+        assert_eq!(len, Ok(9));
+        assert_eq!(&buf[..9], b"   123.45");
+    }
+
+    #[cfg(feature = "print_float")]
+    #[test]
+    fn dense_field_follows_merges_after_overflow() {
+        // 123456792 needs 9 cells, and the exponent form that fits merges its decimal point
+        let mut buf = [b' '; 16];
+        let format = NumberFormat {
+            overflow: Overflow::Exponent,
+            ..Default::default()
+        };
+        let len = format_dense(&mut buf, 8, &format, |buf, format| {
+            format_float(buf, 123456789.0f32, 2, format)
+        });
+        assert_eq!(len, Ok(9));
+        assert_eq!(&buf[..9], b" 1.2346E8");
+    }
+
+    #[cfg(feature = "print_float")]
+    #[test]
+    fn float_rounding_carries_into_integer_digits() {
+        let text = formatted::<6>(
+            |buf, f| format_float(buf, 9.999f32, 2, f),
+            Default::default(),
+        );
+        assert_eq!(&text.unwrap(), b" 10.00");
+        let text = formatted::<6>(
+            |buf, f| format_float(buf, -0.004f32, 2, f),
+            Default::default(),
+        );
+        assert_eq!(&text.unwrap(), b" -0.00");
+    }
+
+    #[cfg(feature = "print_float")]
+    #[test]
+    fn float_overflow_policies() {
+        let float = |value: f32, overflow: Overflow| {
+            let format = NumberFormat {
+                overflow,
+                ..Default::default()
+            };
+            formatted::<5>(|buf, f| format_float(buf, value, 2, f), format)
+        };
+        assert_eq!(
+            float(123.456, Overflow::Error),
+            Err(FormatError::ValueTooLong)
+        );
+        assert_eq!(float(123.456, Overflow::ReducePrecision), Ok(*b"123.5"));
+        assert_eq!(
+            float(123456.0, Overflow::ReducePrecision),
+            Err(FormatError::ValueTooLong)
+        );
+        assert_eq!(float(123456.0, Overflow::Exponent), Ok(*b"1.2E5"));
+        assert_eq!(float(123456.0, Overflow::Saturate), Ok(*b">9999"));
+        assert_eq!(float(-123456.0, Overflow::Saturate), Ok(*b"<-999"));
+    }
+}
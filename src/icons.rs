@@ -0,0 +1,85 @@
+// 5x7 icons and symbols in the same column layout as FONT5X7 (bit 0 is the top row), meant to be
+// loaded with set_custom_glyph and printed alongside text, e.g. set_custom_glyph(0, icons::BELL)
+use crate::CHAR_WIDTH;
+
+// battery from empty to full in 25% steps
+pub const BATTERY: [[u8; CHAR_WIDTH]; 5] = [
+    [0x7E, 0x43, 0x43, 0x43, 0x7E], // 0%
+    [0x7E, 0x63, 0x63, 0x63, 0x7E], // 25%
+    [0x7E, 0x73, 0x73, 0x73, 0x7E], // 50%
+    [0x7E, 0x7B, 0x7B, 0x7B, 0x7E], // 75%
+    [0x7E, 0x7F, 0x7F, 0x7F, 0x7E], // 100%
+];
+
+// signal strength from no bars to all five bars
+pub const SIGNAL: [[u8; CHAR_WIDTH]; 6] = [
+    [0x40, 0x40, 0x40, 0x40, 0x40],
+    [0x70, 0x40, 0x40, 0x40, 0x40],
+    [0x70, 0x78, 0x40, 0x40, 0x40],
+    [0x70, 0x78, 0x7C, 0x40, 0x40],
+    [0x70, 0x78, 0x7C, 0x7E, 0x40],
+    [0x70, 0x78, 0x7C, 0x7E, 0x7F],
+];
+
+pub const PLAY: [u8; CHAR_WIDTH] = [0x7F, 0x3E, 0x1C, 0x08, 0x00];
+pub const PAUSE: [u8; CHAR_WIDTH] = [0x7F, 0x7F, 0x00, 0x7F, 0x7F];
+pub const STOP: [u8; CHAR_WIDTH] = [0x3E, 0x3E, 0x3E, 0x3E, 0x3E];
+pub const RECORD: [u8; CHAR_WIDTH] = [0x1C, 0x3E, 0x3E, 0x3E, 0x1C];
+
+pub const DEGREES_C: [u8; CHAR_WIDTH] = [0x03, 0x03, 0x7C, 0x44, 0x44];
+pub const DEGREES_F: [u8; CHAR_WIDTH] = [0x03, 0x03, 0x7C, 0x14, 0x04];
+
+pub const CHECK: [u8; CHAR_WIDTH] = [0x08, 0x10, 0x20, 0x18, 0x06];
+pub const CROSS: [u8; CHAR_WIDTH] = [0x22, 0x14, 0x08, 0x14, 0x22];
+pub const WARNING: [u8; CHAR_WIDTH] = [0x78, 0x7E, 0x51, 0x7E, 0x78];
+pub const BELL: [u8; CHAR_WIDTH] = [0x10, 0x1E, 0x5F, 0x1E, 0x10];
+pub const LOCK: [u8; CHAR_WIDTH] = [0x78, 0x7F, 0x69, 0x7F, 0x78];
+pub const UNLOCK: [u8; CHAR_WIDTH] = [0x78, 0x7F, 0x69, 0x79, 0x78];
+
+// animation frames for an hourglass with the sand running from top to bottom
+pub const HOURGLASS: [[u8; CHAR_WIDTH]; 4] = [
+    [0x63, 0x57, 0x4F, 0x57, 0x63],
+    [0x63, 0x57, 0x6B, 0x57, 0x63],
+    [0x63, 0x75, 0x6B, 0x75, 0x63],
+    [0x63, 0x75, 0x79, 0x75, 0x63],
+];
+
+pub const ARROW_UP: [u8; CHAR_WIDTH] = [0x04, 0x02, 0x7F, 0x02, 0x04];
+pub const ARROW_UP_RIGHT: [u8; CHAR_WIDTH] = [0x20, 0x10, 0x0A, 0x06, 0x0E];
+pub const ARROW_RIGHT: [u8; CHAR_WIDTH] = [0x08, 0x08, 0x2A, 0x1C, 0x08];
+pub const ARROW_DOWN_RIGHT: [u8; CHAR_WIDTH] = [0x02, 0x04, 0x28, 0x30, 0x38];
+pub const ARROW_DOWN: [u8; CHAR_WIDTH] = [0x10, 0x20, 0x7F, 0x20, 0x10];
+pub const ARROW_DOWN_LEFT: [u8; CHAR_WIDTH] = [0x38, 0x30, 0x28, 0x04, 0x02];
+pub const ARROW_LEFT: [u8; CHAR_WIDTH] = [0x08, 0x1C, 0x2A, 0x08, 0x08];
+pub const ARROW_UP_LEFT: [u8; CHAR_WIDTH] = [0x0E, 0x06, 0x0A, 0x10, 0x20];
+
+// arrows in clockwise order starting from up, e.g. for compass headings in 45 degree steps
+pub const ARROWS: [[u8; CHAR_WIDTH]; 8] = [
+    ARROW_UP,
+    ARROW_UP_RIGHT,
+    ARROW_RIGHT,
+    ARROW_DOWN_RIGHT,
+    ARROW_DOWN,
+    ARROW_DOWN_LEFT,
+    ARROW_LEFT,
+    ARROW_UP_LEFT,
+];
+
+pub fn battery(percent: u8) -> [u8; CHAR_WIDTH] {
+    let step = (percent.min(100) as usize * (BATTERY.len() - 1) + 50) / 100;
+    BATTERY[step]
+}
+
+pub fn signal(level: u8, max_level: u8) -> [u8; CHAR_WIDTH] {
+    if max_level == 0 {
+        return SIGNAL[0];
+    }
+    let bars = (level.min(max_level) as usize * (SIGNAL.len() - 1) + max_level as usize / 2)
+        / max_level as usize;
+    SIGNAL[bars]
+}
+
+pub fn arrow(degrees: u16) -> [u8; CHAR_WIDTH] {
+    // round to the nearest 45 degree step
+    ARROWS[((degrees as usize % 360 + 22) / 45) % ARROWS.len()]
+}
@@ -1,19 +1,59 @@
 #![no_std]
 
+mod attributes;
+mod auto_brightness;
+mod bar;
+mod blank_dimming;
+mod clock;
+mod config;
 mod control_word;
+mod fade;
+mod font;
 mod font5x7;
-
-pub use control_word::PeakCurrent;
+mod format;
+pub mod icons;
+mod luminance;
+mod power;
+mod sparkline;
+mod transition;
+mod vu_meter;
+
+pub use attributes::TextAttributes;
+pub use auto_brightness::{AutoBrightness, LightSensor, DEFAULT_BRIGHTNESS_CURVE};
+pub use bar::{render_bar, BarStyle};
+pub use blank_dimming::{BlankDimmer, BlankPwm, BLANK_DIM_MAX};
+pub use clock::{format_duration, format_time, TimeFormat, TimeLayout};
+pub use config::DisplayConfig;
 use control_word::*;
+pub use control_word::{DataOutMode, ExtOscPrescaler, Oscillator, PeakCurrent};
 use core::cell::RefCell;
+use core::ops::Range;
+use core::time::Duration;
 use embedded_hal::digital::{ErrorType, OutputPin};
+pub use fade::{Easing, Fade, FadeScale};
 pub use font5x7::FONT5X7;
+pub use format::{
+    format_fixed, format_int, format_si, Align, FormatError, Grouping, NumberFormat, Overflow,
+    Padding, Radix,
+};
+#[cfg(feature = "print_float")]
+pub use format::{format_float, format_si_float};
+pub use luminance::LUMINANCE_MAX;
 use num_traits::{ToPrimitive, Zero};
+pub use power::{estimate_current_ua, PowerState};
+pub use sparkline::{Sparkline, SparklineScale, SparklineStyle};
+pub use transition::{Frame, Transition, TransitionEffect};
+pub use vu_meter::{VuLayout, VuMeter};
 
 pub const CHAR_HEIGHT: usize = 7;
 pub const CHAR_WIDTH: usize = 5;
 const DEVICE_CHARS: u8 = 4;
 
+// user-defined glyphs are mapped to the top byte codes, or the start of the Unicode private use area
+pub const CUSTOM_GLYPH_COUNT: usize = 8;
+pub const CUSTOM_GLYPH_START: u8 = 0xF8;
+pub const CUSTOM_GLYPH_CHAR_START: char = '\u{E000}';
+
 pub struct UnconfiguredPin;
 
 impl OutputPin for UnconfiguredPin {
@@ -34,6 +74,8 @@ impl ErrorType for UnconfiguredPin {
 pub enum Hcms29xxError<PinErr> {
     PinNotConfigured,
     ValueTooLong,
+    InvalidGlyphIndex,
+    InvalidCellRange,
     DataPinError(PinErr),
     RsPinError(PinErr),
     ClkPinError(PinErr),
@@ -41,6 +83,28 @@ pub enum Hcms29xxError<PinErr> {
     BlankPinError(PinErr),
     OscSelPinError(PinErr),
     ResetPinError(PinErr),
+    // the light sensor returned an error, read it with AutoBrightness::update for the details
+    SensorError,
+}
+
+impl<PinErr> From<FormatError> for Hcms29xxError<PinErr> {
+    fn from(error: FormatError) -> Self {
+        match error {
+            FormatError::ValueTooLong => Hcms29xxError::ValueTooLong,
+        }
+    }
+}
+
+// Views rows of columns as a single slice, like the slice as_flattened methods which need a
+// newer Rust than the toolchain pinned for AVR targets.
+fn flatten<const W: usize>(rows: &[[u8; W]]) -> &[u8] {
+    // SAFETY: [[u8; W]] has the same layout as W * rows.len() u8s
+    unsafe { core::slice::from_raw_parts(rows.as_ptr().cast(), rows.len() * W) }
+}
+
+fn flatten_mut<const W: usize>(rows: &mut [[u8; W]]) -> &mut [u8] {
+    // SAFETY: [[u8; W]] has the same layout as W * rows.len() u8s
+    unsafe { core::slice::from_raw_parts_mut(rows.as_mut_ptr().cast(), rows.len() * W) }
 }
 
 pub struct Hcms29xx<
@@ -72,7 +136,27 @@ pub struct Hcms29xx<
     control_word_1: ControlWord1,
     // state kept locally to simplify/reduce overall code size
     data_out_mode: DataOutMode,
-    font_ascii_start_index: u8,
+    oscillator: Oscillator,
+    custom_glyphs: [[u8; CHAR_WIDTH]; CUSTOM_GLYPH_COUNT],
+    dense_numbers: bool,
+    // last frame printed, before text attributes are applied
+    frame: Frame<NUM_CHARS>,
+    attributes: [TextAttributes; NUM_CHARS],
+    blink_visible: bool,
+    // brightness is lowered from control_word_0 when the frame would draw more than the limit
+    current_limit_ma: Option<u16>,
+    lit_dots: usize,
+    sent_control_word_0: ControlWord0,
+    // blank pin level, which the dimmer toggles, and the blanking asked for through the API
+    blanked: bool,
+    blank_requested: bool,
+    in_reset: bool,
+    // state entered after idle_timeout ticks without printing, left on the next print
+    idle_timeout: Option<(u32, PowerState)>,
+    idle_ticks: u32,
+    idle: bool,
+    refresh_interval: Option<u32>,
+    refresh_ticks: u32,
 }
 
 impl<
@@ -147,10 +231,23 @@ where
             control_word_0: ControlWord0::default(),
             control_word_1: ControlWord1::default(),
             data_out_mode: DataOutMode::Serial,
-            #[cfg(feature = "avr-progmem")]
-            font_ascii_start_index: FONT5X7.load_at(0) - 1,
-            #[cfg(not(feature = "avr-progmem"))]
-            font_ascii_start_index: FONT5X7[0] - 1,
+            oscillator: Oscillator::Internal,
+            custom_glyphs: [[0x00; CHAR_WIDTH]; CUSTOM_GLYPH_COUNT],
+            dense_numbers: false,
+            frame: [[0x00; CHAR_WIDTH]; NUM_CHARS],
+            attributes: [TextAttributes::NONE; NUM_CHARS],
+            blink_visible: true,
+            current_limit_ma: None,
+            lit_dots: 0,
+            sent_control_word_0: ControlWord0::default(),
+            blanked: true,
+            blank_requested: false,
+            in_reset: false,
+            idle_timeout: None,
+            idle_ticks: 0,
+            idle: false,
+            refresh_interval: None,
+            refresh_ticks: 0,
         })
     }
 
@@ -169,302 +266,581 @@ where
     pub fn begin(&mut self) -> Result<(), Hcms29xxError<PinErr>> {
         self.clear()?;
 
-        self.update_control_word(self.control_word_0.bits())?;
+        self.send_control_word_0()?;
         self.update_control_word(self.control_word_1.bits())?;
 
         Ok(())
     }
 
+    // blanks the display and clears any text attributes
     pub fn clear(&mut self) -> Result<(), Hcms29xxError<PinErr>> {
-        self.set_dot_data()?;
-        for _ in 0..NUM_CHARS * CHAR_WIDTH {
-            self.send_byte(0x00)?;
+        self.attributes = [TextAttributes::NONE; NUM_CHARS];
+        self.print_frame(&[[0x00; CHAR_WIDTH]; NUM_CHARS])
+    }
+
+    pub fn print_ascii_bytes(&mut self, bytes: &[u8]) -> Result<(), Hcms29xxError<PinErr>> {
+        let frame = self.text_frame(bytes);
+        self.print_frame(&frame)
+    }
+
+    pub fn print_str(&mut self, s: &str) -> Result<(), Hcms29xxError<PinErr>> {
+        let mut buf = [b' '; NUM_CHARS];
+        for (i, c) in s.chars().take(NUM_CHARS).enumerate() {
+            let custom_index = (c as u32).wrapping_sub(CUSTOM_GLYPH_CHAR_START as u32);
+            buf[i] = if c.is_ascii() {
+                c as u8
+            } else if custom_index < CUSTOM_GLYPH_COUNT as u32 {
+                CUSTOM_GLYPH_START + custom_index as u8
+            } else {
+                // no font glyph for other non-ASCII characters, leave blank
+                b' '
+            };
         }
-        self.end_transfer()?;
+        self.print_ascii_bytes(&buf)
+    }
+
+    pub fn set_custom_glyph(
+        &mut self,
+        index: usize,
+        cols: [u8; CHAR_WIDTH],
+    ) -> Result<(), Hcms29xxError<PinErr>> {
+        // takes effect the next time text is printed
+        let glyph = self
+            .custom_glyphs
+            .get_mut(index)
+            .ok_or(Hcms29xxError::InvalidGlyphIndex)?;
+        *glyph = cols;
         Ok(())
     }
 
-    pub fn print_ascii_bytes(&mut self, bytes: &[u8]) -> Result<(), Hcms29xxError<PinErr>> {
-        self.set_dot_data()?;
-        for i in 0..NUM_CHARS {
-            if i >= bytes.len() || bytes[i] < self.font_ascii_start_index {
-                break;
-            }
-            let char_index: usize = (bytes[i] - self.font_ascii_start_index) as usize * CHAR_WIDTH;
-            for col in 0..CHAR_WIDTH {
-                #[cfg(feature = "avr-progmem")]
-                self.send_byte(FONT5X7.load_at(char_index + col))?;
-                #[cfg(not(feature = "avr-progmem"))]
-                self.send_byte(FONT5X7[char_index + col])?;
+    fn glyph_col(&self, byte: u8, col: usize) -> u8 {
+        if byte >= CUSTOM_GLYPH_START {
+            return self.custom_glyphs[(byte - CUSTOM_GLYPH_START) as usize][col];
+        }
+        font::glyph_col(byte, col)
+    }
+
+    // columns past the end of the display are dropped and missing ones are left blank
+    pub fn print_cols(&mut self, cols: &[u8]) -> Result<(), Hcms29xxError<PinErr>> {
+        let mut frame = [[0x00; CHAR_WIDTH]; NUM_CHARS];
+        for (dots, &col) in frame.iter_mut().flatten().zip(cols) {
+            *dots = col;
+        }
+        self.print_frame(&frame)
+    }
+
+    // Renders text into a frame without sending it, e.g. as the start or end of a Transition.
+    // The text stops at the first byte below the font's range, e.g. a NUL terminator.
+    pub fn text_frame(&self, bytes: &[u8]) -> Frame<NUM_CHARS> {
+        let mut frame = [[0x00; CHAR_WIDTH]; NUM_CHARS];
+        let text = bytes.iter().take_while(|&&byte| !font::ends_text(byte));
+        for (cell, &byte) in frame.iter_mut().zip(text) {
+            for (col, dots) in cell.iter_mut().enumerate() {
+                *dots = self.glyph_col(byte, col);
             }
         }
-        self.end_transfer()?;
+        frame
+    }
+
+    pub fn print_frame(&mut self, frame: &Frame<NUM_CHARS>) -> Result<(), Hcms29xxError<PinErr>> {
+        self.frame = *frame;
+        self.send_frame()?;
+
+        self.idle_ticks = 0;
+        if self.idle {
+            self.idle = false;
+            self.set_power_state(PowerState::Active)?;
+        }
         Ok(())
     }
 
-    pub fn print_cols(&mut self, cols: &[u8]) -> Result<(), Hcms29xxError<PinErr>> {
+    // attributes apply to whatever is shown in the cells and are kept until changed or cleared
+    pub fn set_attributes(
+        &mut self,
+        cells: Range<usize>,
+        attributes: TextAttributes,
+    ) -> Result<(), Hcms29xxError<PinErr>> {
+        self.attributes
+            .get_mut(cells)
+            .ok_or(Hcms29xxError::InvalidCellRange)?
+            .fill(attributes);
+        self.send_frame()
+    }
+
+    pub fn clear_attributes(&mut self) -> Result<(), Hcms29xxError<PinErr>> {
+        self.attributes = [TextAttributes::NONE; NUM_CHARS];
+        self.send_frame()
+    }
+
+    // toggles blinking cells between shown and hidden, e.g. call every half second
+    pub fn tick_blink(&mut self) -> Result<(), Hcms29xxError<PinErr>> {
+        self.blink_visible = !self.blink_visible;
+        if self
+            .attributes
+            .iter()
+            .any(|attributes| attributes.contains(TextAttributes::BLINK))
+        {
+            self.send_frame()?;
+        }
+        Ok(())
+    }
+
+    // a cell of the current frame with its text attributes applied
+    fn cell(&self, index: usize) -> [u8; CHAR_WIDTH] {
+        self.attributes[index].apply(self.frame[index], self.blink_visible)
+    }
+
+    // sends the current frame with text attributes applied
+    fn send_frame(&mut self) -> Result<(), Hcms29xxError<PinErr>> {
+        // bit 7 isn't a row, but glyphs and raw columns can still set it
+        let lit_dots = (0..NUM_CHARS)
+            .flat_map(|index| self.cell(index))
+            .map(|dots| (dots & 0x7F).count_ones() as usize)
+            .sum();
+
+        // dim before sending a brighter frame and brighten after sending a darker one, so the
+        // limit isn't exceeded in between
+        let control_word_0 = self.limited_control_word_0(lit_dots);
+        let dim_first =
+            control_word_0.brightness_bits() < self.sent_control_word_0.brightness_bits();
+        if dim_first {
+            self.update_control_word(control_word_0.bits())?;
+            self.sent_control_word_0 = control_word_0;
+        }
+
         self.set_dot_data()?;
-        for &byte in cols {
-            self.send_byte(byte)?;
+        for index in 0..NUM_CHARS {
+            for dots in self.cell(index) {
+                self.send_byte(dots)?;
+            }
         }
         self.end_transfer()?;
+        self.lit_dots = lit_dots;
+
+        if control_word_0 != self.sent_control_word_0 {
+            self.send_control_word_0()?;
+        }
         Ok(())
     }
 
+    // draws value out of max as a bar after an optional text label, e.g. print_bar(42, 100, BarStyle::Solid, b"VOL")
+    pub fn print_bar(
+        &mut self,
+        value: i32,
+        max: i32,
+        style: BarStyle,
+        label: &[u8],
+    ) -> Result<(), Hcms29xxError<PinErr>> {
+        if label.len() > NUM_CHARS {
+            return Err(Hcms29xxError::ValueTooLong);
+        }
+
+        let mut frame = self.text_frame(label);
+        bar::render_bar(flatten_mut(&mut frame[label.len()..]), value, max, style);
+        self.print_frame(&frame)
+    }
+
+    pub fn print_sparkline<const SAMPLES: usize>(
+        &mut self,
+        sparkline: &Sparkline<SAMPLES>,
+    ) -> Result<(), Hcms29xxError<PinErr>> {
+        let mut cols = [[0x00; CHAR_WIDTH]; NUM_CHARS];
+        sparkline.render(flatten_mut(&mut cols));
+        self.print_cols(flatten(&cols))
+    }
+
+    pub fn print_vu_meter(&mut self, meter: &VuMeter) -> Result<(), Hcms29xxError<PinErr>> {
+        let mut cols = [[0x00; CHAR_WIDTH]; NUM_CHARS];
+        meter.render(flatten_mut(&mut cols));
+        self.print_cols(flatten(&cols))
+    }
+
     pub fn print_int<T>(&mut self, value: T) -> Result<(), Hcms29xxError<PinErr>>
     where
         T: Copy + Zero + ToPrimitive,
     {
-        // avoid using 64-bit arithmetic for smaller displays
-        if NUM_CHARS <= 10 {
-            self.print_int_32bit(value)
-        } else {
-            self.print_int_64bit(value)
-        }
+        self.print_int_with(value, &NumberFormat::default())
     }
 
-    fn print_int_32bit<T>(&mut self, value: T) -> Result<(), Hcms29xxError<PinErr>>
+    pub fn print_int_with<T>(
+        &mut self,
+        value: T,
+        format: &NumberFormat,
+    ) -> Result<(), Hcms29xxError<PinErr>>
     where
         T: Copy + Zero + ToPrimitive,
     {
-        const fn max_unsigned_for_chars(chars: usize) -> u32 {
-            let mut power_of_10 = 1u32;
-            let mut i = 0;
-            while i < chars {
-                power_of_10 = power_of_10.saturating_mul(10);
-                i += 1;
-            }
-            power_of_10.saturating_sub(1)
-        }
-
-        const fn max_signed_for_chars(chars: usize) -> i32 {
-            max_unsigned_for_chars(chars - 1) as i32
-        }
-
-        let (mut has_sign, mut value) = if let Some(signed_val) = value.to_i32() {
-            let max_signed = max_signed_for_chars(NUM_CHARS);
-            if signed_val < -max_signed || signed_val > max_signed {
-                return Err(Hcms29xxError::ValueTooLong);
-            }
-            if signed_val < 0 {
-                (true, (-signed_val) as u32)
-            } else {
-                (false, signed_val as u32)
-            }
-        } else if let Some(unsigned_val) = value.to_u32() {
-            let max_unsigned = max_unsigned_for_chars(NUM_CHARS);
-            if unsigned_val > max_unsigned {
-                return Err(Hcms29xxError::ValueTooLong);
-            }
-            (false, unsigned_val)
-        } else {
-            return Err(Hcms29xxError::ValueTooLong);
-        };
+        self.print_formatted(format, |buf, format| format::write_int(buf, value, format))
+    }
 
-        let mut buf = [b' '; NUM_CHARS];
-        buf[NUM_CHARS - 1] = b'0';
-
-        for index in (0..NUM_CHARS).rev() {
-            buf[index] = if value > 0 {
-                let digit = b'0' + (value % 10) as u8;
-                value /= 10;
-                digit
-            } else if has_sign {
-                has_sign = false;
-                b'-'
-            } else {
-                break;
-            };
-        }
+    pub fn print_fixed<T>(
+        &mut self,
+        value: T,
+        scale: u8,
+        precision: u8,
+    ) -> Result<(), Hcms29xxError<PinErr>>
+    where
+        T: Copy + ToPrimitive,
+    {
+        self.print_fixed_with(value, scale, precision, &NumberFormat::default())
+    }
 
-        self.print_ascii_bytes(&buf)
+    pub fn print_fixed_with<T>(
+        &mut self,
+        value: T,
+        scale: u8,
+        precision: u8,
+        format: &NumberFormat,
+    ) -> Result<(), Hcms29xxError<PinErr>>
+    where
+        T: Copy + ToPrimitive,
+    {
+        self.print_formatted(format, |buf, format| {
+            format::write_fixed(buf, value, scale, precision, format)
+        })
     }
 
-    fn print_int_64bit<T>(&mut self, value: T) -> Result<(), Hcms29xxError<PinErr>>
+    #[cfg(feature = "print_float")]
+    pub fn print_float<T>(&mut self, value: T, precision: u8) -> Result<(), Hcms29xxError<PinErr>>
     where
-        T: Copy + Zero + ToPrimitive,
+        T: Copy + ToPrimitive,
     {
-        const fn max_unsigned_64_for_chars(chars: usize) -> u64 {
-            let mut power_of_10 = 1u64;
-            let mut i = 0;
-            while i < chars {
-                power_of_10 = power_of_10.saturating_mul(10);
-                i += 1;
-            }
-            power_of_10.saturating_sub(1)
-        }
+        self.print_float_with(value, precision, &NumberFormat::default())
+    }
 
-        const fn max_signed_64_for_chars(chars: usize) -> i64 {
-            max_unsigned_64_for_chars(chars - 1) as i64
-        }
+    #[cfg(feature = "print_float")]
+    pub fn print_float_with<T>(
+        &mut self,
+        value: T,
+        precision: u8,
+        format: &NumberFormat,
+    ) -> Result<(), Hcms29xxError<PinErr>>
+    where
+        T: Copy + ToPrimitive,
+    {
+        self.print_formatted(format, |buf, format| {
+            format::format_float(buf, value, precision, format)
+        })
+    }
 
-        let (mut has_sign, mut value) = if let Some(signed_val) = value.to_i64() {
-            let max_signed = max_signed_64_for_chars(NUM_CHARS);
-            if signed_val < -max_signed || signed_val > max_signed {
-                return Err(Hcms29xxError::ValueTooLong);
-            }
-            if signed_val < 0 {
-                (true, (-signed_val) as u64)
-            } else {
-                (false, signed_val as u64)
-            }
-        } else if let Some(unsigned_val) = value.to_u64() {
-            let max_unsigned = max_unsigned_64_for_chars(NUM_CHARS);
-            if unsigned_val > max_unsigned {
-                return Err(Hcms29xxError::ValueTooLong);
-            }
-            (false, unsigned_val)
-        } else {
-            return Err(Hcms29xxError::ValueTooLong);
-        };
+    // prints value * 10^exponent with an SI prefix and unit, e.g. print_si(4700, -9, b"F") shows " 4.700µF" on
+    // 8 characters
+    pub fn print_si<T>(
+        &mut self,
+        value: T,
+        exponent: i8,
+        unit: &[u8],
+    ) -> Result<(), Hcms29xxError<PinErr>>
+    where
+        T: Copy + ToPrimitive,
+    {
+        self.print_si_with(value, exponent, unit, &NumberFormat::default())
+    }
 
-        let mut buf = [b' '; NUM_CHARS];
-        buf[NUM_CHARS - 1] = b'0';
-
-        for index in (0..NUM_CHARS).rev() {
-            buf[index] = if value > 0 {
-                let digit = b'0' + (value % 10) as u8;
-                value /= 10;
-                digit
-            } else if has_sign {
-                has_sign = false;
-                b'-'
-            } else {
-                break;
-            };
-        }
+    pub fn print_si_with<T>(
+        &mut self,
+        value: T,
+        exponent: i8,
+        unit: &[u8],
+        format: &NumberFormat,
+    ) -> Result<(), Hcms29xxError<PinErr>>
+    where
+        T: Copy + ToPrimitive,
+    {
+        self.print_formatted(format, |buf, format| {
+            format::format_si(buf, value, exponent, unit, format)
+        })
+    }
 
-        self.print_ascii_bytes(&buf)
+    #[cfg(feature = "print_float")]
+    pub fn print_si_float<T>(&mut self, value: T, unit: &[u8]) -> Result<(), Hcms29xxError<PinErr>>
+    where
+        T: Copy + ToPrimitive,
+    {
+        self.print_si_float_with(value, unit, &NumberFormat::default())
     }
 
     #[cfg(feature = "print_float")]
-    pub fn print_float<T>(&mut self, value: T, precision: u8) -> Result<(), Hcms29xxError<PinErr>>
+    pub fn print_si_float_with<T>(
+        &mut self,
+        value: T,
+        unit: &[u8],
+        format: &NumberFormat,
+    ) -> Result<(), Hcms29xxError<PinErr>>
     where
         T: Copy + ToPrimitive,
     {
-        let float_val = value.to_f32().ok_or(Hcms29xxError::ValueTooLong)?;
-        let mut buf = [b' '; NUM_CHARS];
+        self.print_formatted(format, |buf, format| {
+            format::format_si_float(buf, value, unit, format)
+        })
+    }
 
-        // special values
-        if float_val.is_nan() {
-            buf[(NUM_CHARS - 3)..].copy_from_slice(b"NaN");
-            return self.print_ascii_bytes(&buf);
-        }
-        if float_val.is_infinite() {
-            let inf_str = if float_val.is_sign_positive() {
-                b"+Inf"
-            } else {
-                b"-Inf"
-            };
-            buf[(NUM_CHARS - inf_str.len())..].copy_from_slice(inf_str);
-            return self.print_ascii_bytes(&buf);
-        }
+    // in dense mode, number printing uses print_dense so a decimal point, ':' or ',' shares a
+    // cell with the digit before it
+    pub fn set_dense_numbers(&mut self, enabled: bool) {
+        self.dense_numbers = enabled;
+    }
 
-        let is_negative = float_val.is_sign_negative();
-        let abs_val = float_val.abs();
+    pub fn print_dense(&mut self, bytes: &[u8]) -> Result<(), Hcms29xxError<PinErr>> {
+        self.write_dense(bytes, true)
+    }
 
-        // check value bounds
-        let integer_digits = if abs_val < 1.0 {
-            1
-        } else {
-            let mut temp = abs_val as u32;
-            let mut digits = 0;
-            while temp > 0 {
-                digits += 1;
-                temp /= 10;
-            }
-            digits
-        };
-        let total_chars = (if is_negative { 1 } else { 0 })
-            + integer_digits
-            + (if precision > 0 { 1 } else { 0 })
-            + precision as usize;
-        if total_chars > NUM_CHARS {
+    // merged punctuation is left blank when punct_visible is false, e.g. for a blinking colon
+    fn write_dense(
+        &mut self,
+        bytes: &[u8],
+        punct_visible: bool,
+    ) -> Result<(), Hcms29xxError<PinErr>> {
+        // drop leading spaces until the text fits
+        let mut start = 0;
+        let mut cells = bytes.len() - font::dense_merge_count(bytes);
+        while cells > NUM_CHARS && bytes.get(start) == Some(&b' ') {
+            start += 1;
+            cells -= 1;
+        }
+        if cells > NUM_CHARS {
             return Err(Hcms29xxError::ValueTooLong);
         }
 
-        // scale number to integer value for formatting
-        let mut pos = NUM_CHARS;
-        let mut scale_factor = 1.0f32;
-        for _ in 0..precision {
-            scale_factor *= 10.0;
-        }
-        let rounded_val = abs_val * scale_factor + 0.5;
-        let mut digits = rounded_val as u32;
+        let mut frame = [[0x00; CHAR_WIDTH]; NUM_CHARS];
+        let mut frame_cells = frame.iter_mut();
+        let mut i = start;
+        while let Some(cell) = frame_cells.next().filter(|_| i < bytes.len()) {
+            let byte = bytes[i];
+            let punct_col = match bytes.get(i + 1) {
+                Some(&next) if byte.is_ascii_digit() => font::narrow_punct_col(next),
+                _ => None,
+            };
 
-        for _ in 0..precision {
-            pos -= 1;
-            buf[pos] = b'0' + (digits % 10) as u8;
-            digits /= 10;
+            if let Some(punct_col) = punct_col {
+                for (col, dots) in cell[..font::NARROW_DIGIT_WIDTH].iter_mut().enumerate() {
+                    *dots = font::narrow_digit_byte(byte - b'0', col);
+                }
+                if punct_visible {
+                    cell[CHAR_WIDTH - 1] = punct_col;
+                }
+                i += 2;
+            } else {
+                for (col, dots) in cell.iter_mut().enumerate() {
+                    *dots = self.glyph_col(byte, col);
+                }
+                i += 1;
+            }
         }
+        self.print_frame(&frame)
+    }
 
-        if precision > 0 {
-            pos -= 1;
-            buf[pos] = b'.';
-        }
+    // prints a time of day in seconds since midnight with narrow colons, tick drives the colon blink
+    pub fn print_time(
+        &mut self,
+        seconds: u32,
+        format: &TimeFormat,
+        tick: u32,
+    ) -> Result<(), Hcms29xxError<PinErr>> {
+        self.print_clock(seconds % (24 * 60 * 60), format, false, tick)
+    }
 
-        if digits == 0 {
-            pos -= 1;
-            buf[pos] = b'0';
-        } else {
-            while digits > 0 {
-                // possible for rounding to cause overflow
-                if pos == 0 {
-                    return Err(Hcms29xxError::ValueTooLong);
-                }
+    pub fn print_duration(
+        &mut self,
+        duration: Duration,
+        format: &TimeFormat,
+        tick: u32,
+    ) -> Result<(), Hcms29xxError<PinErr>> {
+        // round up so a countdown only shows zero once it has finished
+        let seconds = duration.as_secs() + (duration.subsec_nanos() > 0) as u64;
+        self.print_clock(seconds.min(u32::MAX as u64) as u32, format, true, tick)
+    }
 
-                pos -= 1;
-                buf[pos] = b'0' + (digits % 10) as u8;
-                digits /= 10;
-            }
-        }
+    fn print_clock(
+        &mut self,
+        seconds: u32,
+        format: &TimeFormat,
+        is_duration: bool,
+        tick: u32,
+    ) -> Result<(), Hcms29xxError<PinErr>> {
+        let mut text = [0; clock::MAX_TIME_LEN];
+        let len = clock::time_text(&mut text, seconds, format, is_duration, NUM_CHARS, true)?;
+        let text = &text[..len];
+
+        // align by cells rather than bytes since each colon shares a cell with a digit
+        let spaces = NUM_CHARS - (len - font::dense_merge_count(text));
+        let start = match format.align {
+            Align::Left => 0,
+            Align::Right => spaces,
+            Align::Center => spaces / 2,
+        };
+        let mut buf = [[b' '; 2]; NUM_CHARS];
+        let buf = flatten_mut(&mut buf);
+        buf[start..start + len].copy_from_slice(text);
+        self.write_dense(&buf[..start + len], clock::colon_visible(format, tick))
+    }
 
-        if is_negative {
-            pos -= 1;
-            buf[pos] = b'-';
+    fn print_formatted<F>(
+        &mut self,
+        format: &NumberFormat,
+        write: F,
+    ) -> Result<(), Hcms29xxError<PinErr>>
+    where
+        F: Fn(&mut [u8], &NumberFormat) -> Result<(), FormatError>,
+    {
+        if !self.dense_numbers {
+            let mut buf = [b' '; NUM_CHARS];
+            write(&mut buf, format)?;
+            return self.print_ascii_bytes(&buf);
         }
 
-        self.print_ascii_bytes(&buf)
+        let mut buf = [[b' '; 2]; NUM_CHARS];
+        let buf = flatten_mut(&mut buf);
+        let len = format::format_dense(buf, NUM_CHARS, format, write)?;
+        self.print_dense(&buf[..len])
     }
 
     #[deprecated(since = "0.2.0", note = "Use print_int instead")]
     pub fn print_i32(&mut self, value: i32) -> Result<(), Hcms29xxError<PinErr>> {
-        self.print_int_32bit(value)
+        self.print_int(value)
     }
 
     #[deprecated(since = "0.2.0", note = "Use print_int instead")]
     pub fn print_u32(&mut self, value: u32) -> Result<(), Hcms29xxError<PinErr>> {
-        self.print_int_32bit(value)
+        self.print_int(value)
     }
 
     pub fn display_blank(&mut self) -> Result<(), Hcms29xxError<PinErr>> {
-        self.blank
-            .borrow_mut()
-            .set_high()
-            .map_err(Hcms29xxError::BlankPinError)?;
-        Ok(())
+        self.blank_requested = true;
+        self.set_blank_pin(true)
     }
 
     pub fn display_sleep(&mut self) -> Result<(), Hcms29xxError<PinErr>> {
         self.control_word_0.set_wake_bit(SleepMode::Sleep);
-        self.update_control_word(self.control_word_0.bits())?;
+        self.send_control_word_0()?;
         Ok(())
     }
 
     pub fn display_wake(&mut self) -> Result<(), Hcms29xxError<PinErr>> {
         self.control_word_0.set_wake_bit(SleepMode::Normal);
-        self.update_control_word(self.control_word_0.bits())?;
+        self.send_control_word_0()?;
         Ok(())
     }
 
     pub fn display_unblank(&mut self) -> Result<(), Hcms29xxError<PinErr>> {
-        self.blank
-            .borrow_mut()
-            .set_low()
-            .map_err(Hcms29xxError::BlankPinError)?;
+        self.blank_requested = false;
+        self.set_blank_pin(false)
+    }
+
+    fn set_blank_pin(&mut self, blanked: bool) -> Result<(), Hcms29xxError<PinErr>> {
+        let mut blank = self.blank.borrow_mut();
+        if blanked {
+            blank.set_high()
+        } else {
+            blank.set_low()
+        }
+        .map_err(Hcms29xxError::BlankPinError)?;
+        self.blanked = blanked;
+        Ok(())
+    }
+
+    // Blanks and unblanks the display for the dimmer's duty cycle while Active, requires the
+    // blank pin. The power state isn't changed, and the display is left blanked in any other.
+    pub fn tick_blank_dimming(
+        &mut self,
+        dimmer: &mut BlankDimmer,
+    ) -> Result<(), Hcms29xxError<PinErr>> {
+        let lit = dimmer.step() && self.power_state() == PowerState::Active;
+        if lit == self.blanked {
+            self.set_blank_pin(!lit)?;
+        }
+        Ok(())
+    }
+
+    // the state asked for, which starts Active as the blank pin may be wired low
+    pub fn power_state(&self) -> PowerState {
+        if self.in_reset {
+            PowerState::Off
+        } else if self.is_sleeping() {
+            PowerState::Sleep
+        } else if self.blank_requested {
+            PowerState::Blanked
+        } else {
+            PowerState::Active
+        }
+    }
+
+    // moves between power states, restoring the control words and dot data when leaving Off,
+    // requires the blank pin, and the reset pin for Off
+    pub fn set_power_state(&mut self, state: PowerState) -> Result<(), Hcms29xxError<PinErr>> {
+        self.idle = false;
+        if state == PowerState::Off {
+            self.display_blank()?;
+            self.reset
+                .borrow_mut()
+                .set_low()
+                .map_err(Hcms29xxError::ResetPinError)?;
+            self.in_reset = true;
+            return Ok(());
+        }
+
+        if self.in_reset {
+            self.reset
+                .borrow_mut()
+                .set_high()
+                .map_err(Hcms29xxError::ResetPinError)?;
+            self.in_reset = false;
+            self.restore()?;
+        }
+
+        let sleep = if state == PowerState::Sleep {
+            SleepMode::Sleep
+        } else {
+            SleepMode::Normal
+        };
+        if (self.power_state() == PowerState::Sleep) != (state == PowerState::Sleep) {
+            self.control_word_0.set_wake_bit(sleep);
+            self.send_control_word_0()?;
+        }
+
+        match state {
+            PowerState::Active => self.display_unblank(),
+            _ => self.display_blank(),
+        }
+    }
+
+    // enters the state after the given number of tick_idle calls without printing, waking on
+    // the next print, None disables the timeout
+    pub fn set_idle_timeout(&mut self, timeout: Option<(u32, PowerState)>) {
+        self.idle_timeout = timeout;
+        self.idle_ticks = 0;
+    }
+
+    pub fn tick_idle(&mut self) -> Result<(), Hcms29xxError<PinErr>> {
+        let Some((ticks, state)) = self.idle_timeout else {
+            return Ok(());
+        };
+        if self.idle || self.power_state() != PowerState::Active {
+            return Ok(());
+        }
+
+        self.idle_ticks = self.idle_ticks.saturating_add(1);
+        if self.idle_ticks >= ticks {
+            self.set_power_state(state)?;
+            self.idle = true;
+        }
         Ok(())
     }
 
+    // resends the control words and dot data, e.g. after the display has been reset
+    fn restore(&mut self) -> Result<(), Hcms29xxError<PinErr>> {
+        // the display comes out of reset in serial mode, so control word 1 reaches every device
+        // before setting their data out mode
+        let data_out_mode = self.data_out_mode;
+        self.data_out_mode = DataOutMode::Serial;
+        self.update_control_word(self.control_word_1.bits())?;
+        self.data_out_mode = data_out_mode;
+
+        self.send_control_word_0()?;
+        self.send_frame()
+    }
+
+    // pulses the reset pin, then restores the control words and dot data last sent
     pub fn reset(&mut self) -> Result<(), Hcms29xxError<PinErr>> {
         self.reset
             .borrow_mut()
@@ -474,18 +850,122 @@ where
             .borrow_mut()
             .set_high()
             .map_err(Hcms29xxError::ResetPinError)?;
+        self.in_reset = false;
+        self.restore()
+    }
+
+    // resends the control words and dot data last sent, e.g. after suspected corruption from
+    // noise on the lines
+    pub fn recover(&mut self) -> Result<(), Hcms29xxError<PinErr>> {
+        if self.in_reset {
+            return Ok(());
+        }
+        self.restore()
+    }
+
+    // recovers every given number of tick_refresh calls, None disables refreshing
+    pub fn set_refresh_interval(&mut self, ticks: Option<u32>) {
+        self.refresh_interval = ticks;
+        self.refresh_ticks = 0;
+    }
+
+    pub fn tick_refresh(&mut self) -> Result<(), Hcms29xxError<PinErr>> {
+        let Some(interval) = self.refresh_interval else {
+            return Ok(());
+        };
+
+        self.refresh_ticks = self.refresh_ticks.saturating_add(1);
+        if self.refresh_ticks >= interval {
+            self.refresh_ticks = 0;
+            self.recover()?;
+        }
         Ok(())
     }
 
     pub fn set_brightness(&mut self, brightness: u8) -> Result<(), Hcms29xxError<PinErr>> {
         self.control_word_0.set_brightness_bits(brightness);
-        self.update_control_word(self.control_word_0.bits())?;
+        self.send_control_word_0()?;
         Ok(())
     }
 
     pub fn set_peak_current(&mut self, current: PeakCurrent) -> Result<(), Hcms29xxError<PinErr>> {
         self.control_word_0.set_peak_current_bits(current);
-        self.update_control_word(self.control_word_0.bits())?;
+        self.send_control_word_0()?;
+        Ok(())
+    }
+
+    // brightness on a perceptually even scale from 0 (blank) to LUMINANCE_MAX, setting the peak
+    // current and PWM brightness together in a single update
+    pub fn set_luminance(&mut self, level: u8) -> Result<(), Hcms29xxError<PinErr>> {
+        let (current, brightness) = luminance::luminance_setting(level);
+        self.control_word_0.set_peak_current_bits(current);
+        self.control_word_0.set_brightness_bits(brightness);
+        self.send_control_word_0()?;
+        Ok(())
+    }
+
+    // reads the light sensor and follows it with set_luminance
+    pub fn tick_auto_brightness<S: LightSensor>(
+        &mut self,
+        auto: &mut AutoBrightness<S>,
+    ) -> Result<(), Hcms29xxError<PinErr>> {
+        if let Some(level) = auto.update().map_err(|_| Hcms29xxError::SensorError)? {
+            self.set_luminance(level)?;
+        }
+        Ok(())
+    }
+
+    // applies the fade's next level, returns false once the fade has finished
+    pub fn tick_fade(&mut self, fade: &mut Fade) -> Result<bool, Hcms29xxError<PinErr>> {
+        if let Some(level) = fade.tick() {
+            match fade.scale() {
+                FadeScale::Brightness => self.set_brightness(level)?,
+                FadeScale::Luminance => self.set_luminance(level)?,
+            }
+        }
+        Ok(!fade.is_finished())
+    }
+
+    // lowers the brightness below the one set when the estimated LED current for the displayed
+    // dots would exceed the limit, None removes the limit
+    pub fn set_current_limit(
+        &mut self,
+        limit_ma: Option<u16>,
+    ) -> Result<(), Hcms29xxError<PinErr>> {
+        self.current_limit_ma = limit_ma;
+        self.send_control_word_0()
+    }
+
+    // estimated LED supply current for the displayed dots at the brightness sent, without the
+    // logic supply current of each device in the chain, see estimate_current_ua
+    pub fn estimated_current_ua(&self) -> u32 {
+        if self.power_state() != PowerState::Active {
+            return 0;
+        }
+        power::estimate_current_ua(
+            self.lit_dots,
+            self.sent_control_word_0.peak_current(),
+            self.sent_control_word_0.brightness_bits(),
+        )
+    }
+
+    fn limited_control_word_0(&self, lit_dots: usize) -> ControlWord0 {
+        let mut control_word = self.control_word_0;
+        if let Some(limit_ma) = self.current_limit_ma {
+            control_word.set_brightness_bits(power::limit_brightness(
+                lit_dots,
+                control_word.peak_current(),
+                control_word.brightness_bits(),
+                limit_ma,
+            ));
+        }
+        control_word
+    }
+
+    fn send_control_word_0(&mut self) -> Result<(), Hcms29xxError<PinErr>> {
+        let control_word = self.limited_control_word_0(self.lit_dots);
+        self.update_control_word(control_word.bits())?;
+        self.sent_control_word_0 = control_word;
         Ok(())
     }
 
@@ -508,6 +988,7 @@ where
             .borrow_mut()
             .set_low()
             .map_err(Hcms29xxError::OscSelPinError)?;
+        self.oscillator = Oscillator::External;
         Ok(())
     }
 
@@ -516,6 +997,7 @@ where
             .borrow_mut()
             .set_high()
             .map_err(Hcms29xxError::OscSelPinError)?;
+        self.oscillator = Oscillator::Internal;
         Ok(())
     }
 
@@ -541,6 +1023,66 @@ where
         Ok(())
     }
 
+    // the brightness set, which may be above the one sent while a current limit applies
+    pub fn brightness(&self) -> u8 {
+        self.control_word_0.brightness_bits()
+    }
+
+    pub fn peak_current(&self) -> PeakCurrent {
+        self.control_word_0.peak_current()
+    }
+
+    pub fn is_sleeping(&self) -> bool {
+        self.control_word_0.bits() & ControlWord0::WAKE_BIT == 0
+    }
+
+    // blank pin level, including the dimmer's blanking
+    pub fn is_blanked(&self) -> bool {
+        self.blanked
+    }
+
+    pub fn oscillator(&self) -> Oscillator {
+        self.oscillator
+    }
+
+    pub fn ext_osc_prescaler(&self) -> ExtOscPrescaler {
+        self.control_word_1.ext_osc_prescaler()
+    }
+
+    pub fn data_out_mode(&self) -> DataOutMode {
+        self.data_out_mode
+    }
+
+    pub fn config(&self) -> DisplayConfig {
+        DisplayConfig {
+            brightness: self.brightness(),
+            peak_current: self.peak_current(),
+            oscillator: self.oscillator,
+            ext_osc_prescaler: self.ext_osc_prescaler(),
+            data_out_mode: self.data_out_mode,
+        }
+    }
+
+    pub fn apply_config(&mut self, config: &DisplayConfig) -> Result<(), Hcms29xxError<PinErr>> {
+        match config.oscillator {
+            Oscillator::Internal => self.set_int_osc()?,
+            Oscillator::External => self.set_ext_osc()?,
+        }
+
+        self.control_word_1
+            .set_ext_osc_prescaler_bit(config.ext_osc_prescaler);
+        self.control_word_1
+            .set_data_out_mode_bit(config.data_out_mode);
+        self.update_control_word(self.control_word_1.bits())?;
+        // update local state once change is sent to device
+        self.data_out_mode = config.data_out_mode;
+
+        self.control_word_0.set_brightness_bits(config.brightness);
+        self.control_word_0
+            .set_peak_current_bits(config.peak_current);
+        self.send_control_word_0()
+    }
+
     fn update_control_word(&mut self, control_word: u8) -> Result<(), Hcms29xxError<PinErr>> {
         let times_to_send = if self.data_out_mode == DataOutMode::Serial {
             NUM_CHARS as u8 / DEVICE_CHARS
@@ -0,0 +1,52 @@
+use crate::control_word::ControlWord0;
+use crate::PeakCurrent;
+
+// PWM duty cycle for each brightness level in 1/1000ths, from the datasheet
+const BRIGHTNESS_DUTY: [u32; 16] = [
+    0, 17, 33, 50, 67, 83, 117, 150, 180, 230, 300, 370, 470, 600, 800, 1000,
+];
+// the rows are multiplexed, so each dot is only driven for one of eight row periods
+const ROW_DUTY_DIVISOR: u32 = 8;
+
+fn peak_current_ua(current: PeakCurrent) -> u32 {
+    match current {
+        PeakCurrent::Max4_0Ma => 4_000,
+        PeakCurrent::Max6_4Ma => 6_400,
+        PeakCurrent::Max9_3Ma => 9_300,
+        PeakCurrent::Max12_8Ma => 12_800,
+    }
+}
+
+// Typical average LED supply current in microamps for a number of lit dots. The logic supply
+// current of each device in the chain isn't included, so for long chains add the datasheet figure
+// per 4 characters. Brightness levels above 15 are clamped.
+pub fn estimate_current_ua(lit_dots: usize, current: PeakCurrent, brightness: u8) -> u32 {
+    let duty = BRIGHTNESS_DUTY[brightness.min(ControlWord0::BRIGHTNESS_MASK) as usize];
+    let dot_ua = peak_current_ua(current) * duty / 1000 / ROW_DUTY_DIVISOR;
+    (lit_dots as u32).saturating_mul(dot_ua)
+}
+
+// highest brightness up to the requested one that keeps the estimate within the limit
+pub(crate) fn limit_brightness(
+    lit_dots: usize,
+    current: PeakCurrent,
+    brightness: u8,
+    limit_ma: u16,
+) -> u8 {
+    let limit_ua = limit_ma as u32 * 1000;
+    (0..=brightness.min(ControlWord0::BRIGHTNESS_MASK))
+        .rev()
+        .find(|&brightness| estimate_current_ua(lit_dots, current, brightness) <= limit_ua)
+        .unwrap_or(0)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerState {
+    Active,
+    // display off through the blank pin, everything else running
+    Blanked,
+    // oscillator stopped and display off, dot data and control words are kept by the display
+    Sleep,
+    // held in reset for the lowest power, the driver restores everything when leaving it
+    Off,
+}
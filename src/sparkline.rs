@@ -0,0 +1,119 @@
+use crate::CHAR_HEIGHT;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SparklineStyle {
+    // filled from the bottom row, the lowest level still lights one dot
+    #[default]
+    Bar,
+    Dot,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SparklineScale {
+    // fit the range of the stored samples
+    #[default]
+    Auto,
+    // samples outside the range are clamped
+    Fixed {
+        min: i32,
+        max: i32,
+    },
+}
+
+// History of the last N samples drawn one column per sample, newest at the right, e.g.
+// Sparkline::<40>::new(SparklineStyle::Bar, SparklineScale::Auto) for a 40 column display.
+pub struct Sparkline<const N: usize> {
+    samples: [i32; N],
+    len: usize,
+    next: usize,
+    style: SparklineStyle,
+    scale: SparklineScale,
+}
+
+impl<const N: usize> Sparkline<N> {
+    pub const fn new(style: SparklineStyle, scale: SparklineScale) -> Self {
+        Sparkline {
+            samples: [0; N],
+            len: 0,
+            next: 0,
+            style,
+            scale,
+        }
+    }
+
+    // once full, the oldest sample is dropped
+    pub fn push(&mut self, sample: i32) {
+        if N == 0 {
+            return;
+        }
+        self.samples[self.next] = sample;
+        self.next = (self.next + 1) % N;
+        self.len = (self.len + 1).min(N);
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.next = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn set_style(&mut self, style: SparklineStyle) {
+        self.style = style;
+    }
+
+    pub fn set_scale(&mut self, scale: SparklineScale) {
+        self.scale = scale;
+    }
+
+    // oldest to newest
+    pub fn samples(&self) -> impl Iterator<Item = i32> + '_ {
+        let start = (self.next + N - self.len) % N.max(1);
+        (0..self.len).map(move |i| self.samples[(start + i) % N])
+    }
+
+    // Renders the newest samples right aligned into display columns, e.g. for print_cols.
+    pub fn render(&self, cols: &mut [u8]) {
+        cols.fill(0x00);
+
+        let (min, max) = match self.scale {
+            SparklineScale::Fixed { min, max } => (min, max),
+            SparklineScale::Auto => self
+                .samples()
+                .fold((i32::MAX, i32::MIN), |(min, max), sample| {
+                    (min.min(sample), max.max(sample))
+                }),
+        };
+
+        let shown = self.len.min(cols.len());
+        let offset = cols.len() - shown;
+        for (col, sample) in cols[offset..]
+            .iter_mut()
+            .zip(self.samples().skip(self.len - shown))
+        {
+            let level = level(sample, min, max);
+            *col = match self.style {
+                SparklineStyle::Bar => (0x7F << (CHAR_HEIGHT - 1 - level)) & 0x7F,
+                SparklineStyle::Dot => 0x40 >> level,
+            };
+        }
+    }
+}
+
+// row above the bottom row for sample between min and max, rounded to the nearest row,
+// a flat range is drawn in the middle
+fn level(sample: i32, min: i32, max: i32) -> usize {
+    let top = (CHAR_HEIGHT - 1) as i64;
+    if max <= min {
+        return (top / 2) as usize;
+    }
+    let sample = sample.clamp(min, max) as i64;
+    let (min, max) = (min as i64, max as i64);
+    (((sample - min) * top + (max - min) / 2) / (max - min)) as usize
+}
@@ -0,0 +1,196 @@
+use crate::{flatten, flatten_mut, font, CHAR_HEIGHT, CHAR_WIDTH};
+
+// display contents as columns of dots per character, bit 0 is the top row
+pub type Frame<const NUM_CHARS: usize> = [[u8; CHAR_WIDTH]; NUM_CHARS];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionEffect {
+    // the new frame pushes the old one out to the left
+    SlideLeft,
+    SlideRight,
+    // the new frame rolls in from the bottom
+    RollUp,
+    RollDown,
+    // the new frame is revealed from left to right
+    Wipe,
+    // the new frame is revealed a few random dots at a time
+    Dissolve,
+    // each character spins through digits like a slot machine reel, stopping from left to right
+    SlotMachine,
+}
+
+const ROWS_MASK: u8 = 0x7F;
+// each glyph on a slot machine reel takes a row of spacing
+const REEL_ROWS: usize = CHAR_HEIGHT + 1;
+const REEL_DIGITS_PER_CHAR: usize = 4;
+const DEFAULT_SEED: u32 = 0x2545_F491;
+
+// Steps from one frame to another, one step per tick, so an effect can run alongside other work.
+pub struct Transition<const NUM_CHARS: usize> {
+    from: Frame<NUM_CHARS>,
+    to: Frame<NUM_CHARS>,
+    effect: TransitionEffect,
+    step: u16,
+    steps: u16,
+    seed: u32,
+}
+
+impl<const NUM_CHARS: usize> Transition<NUM_CHARS> {
+    // defaults to one column or row per step for moving effects
+    pub fn new(from: Frame<NUM_CHARS>, to: Frame<NUM_CHARS>, effect: TransitionEffect) -> Self {
+        let cols = (NUM_CHARS * CHAR_WIDTH) as u16;
+        let steps = match effect {
+            TransitionEffect::SlideLeft | TransitionEffect::SlideRight | TransitionEffect::Wipe => {
+                cols
+            }
+            TransitionEffect::RollUp | TransitionEffect::RollDown => CHAR_HEIGHT as u16,
+            TransitionEffect::Dissolve => 16,
+            TransitionEffect::SlotMachine => {
+                (REEL_ROWS * (REEL_DIGITS_PER_CHAR * NUM_CHARS + 1) / 2) as u16
+            }
+        };
+
+        Transition {
+            from,
+            to,
+            effect,
+            step: 0,
+            steps,
+            seed: DEFAULT_SEED,
+        }
+    }
+
+    pub fn set_steps(&mut self, steps: u16) {
+        self.steps = steps.max(1);
+        self.step = self.step.min(self.steps);
+    }
+
+    // changes the order dots are revealed in by Dissolve
+    pub fn set_seed(&mut self, seed: u32) {
+        self.seed = seed;
+    }
+
+    // advances one step, returns false once the transition had already finished
+    pub fn tick(&mut self) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+        self.step += 1;
+        true
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.step >= self.steps
+    }
+
+    pub fn restart(&mut self) {
+        self.step = 0;
+    }
+
+    pub fn frame(&self) -> Frame<NUM_CHARS> {
+        if self.is_finished() {
+            return self.to;
+        }
+
+        let mut frame = [[0x00; CHAR_WIDTH]; NUM_CHARS];
+        let from = flatten(&self.from);
+        let to = flatten(&self.to);
+        let cols = flatten_mut(&mut frame);
+        let total = cols.len();
+        let step = self.step as usize;
+        let steps = self.steps as usize;
+
+        match self.effect {
+            TransitionEffect::SlideLeft => {
+                let shift = step * total / steps;
+                for (i, col) in cols.iter_mut().enumerate() {
+                    *col = if i + shift < total {
+                        from[i + shift]
+                    } else {
+                        to[i + shift - total]
+                    };
+                }
+            }
+            TransitionEffect::SlideRight => {
+                let shift = step * total / steps;
+                for (i, col) in cols.iter_mut().enumerate() {
+                    *col = if i < shift {
+                        to[total - shift + i]
+                    } else {
+                        from[i - shift]
+                    };
+                }
+            }
+            TransitionEffect::RollUp => {
+                let shift = step * CHAR_HEIGHT / steps;
+                for (i, col) in cols.iter_mut().enumerate() {
+                    *col = ((from[i] >> shift) | (to[i] << (CHAR_HEIGHT - shift))) & ROWS_MASK;
+                }
+            }
+            TransitionEffect::RollDown => {
+                let shift = step * CHAR_HEIGHT / steps;
+                for (i, col) in cols.iter_mut().enumerate() {
+                    *col = ((from[i] << shift) | (to[i] >> (CHAR_HEIGHT - shift))) & ROWS_MASK;
+                }
+            }
+            TransitionEffect::Wipe => {
+                let revealed = step * total / steps;
+                cols[..revealed].copy_from_slice(&to[..revealed]);
+                cols[revealed..].copy_from_slice(&from[revealed..]);
+            }
+            TransitionEffect::Dissolve => {
+                for (i, col) in cols.iter_mut().enumerate() {
+                    let mut mask = 0x00;
+                    for row in 0..CHAR_HEIGHT {
+                        let dot = (i * CHAR_HEIGHT + row) as u32;
+                        if (hash(dot ^ self.seed) as usize % steps) < step {
+                            mask |= 1 << row;
+                        }
+                    }
+                    *col = (from[i] & !mask) | (to[i] & mask);
+                }
+            }
+            TransitionEffect::SlotMachine => {
+                for (index, cell) in frame.iter_mut().enumerate() {
+                    *cell = self.reel_cell(index, step, steps);
+                }
+            }
+        }
+
+        frame
+    }
+
+    // the reel for a character runs from its old glyph through a few digits to its new glyph
+    fn reel_cell(&self, index: usize, step: usize, steps: usize) -> [u8; CHAR_WIDTH] {
+        let stop_step = steps * (index + 1) / NUM_CHARS;
+        if step >= stop_step {
+            return self.to[index];
+        }
+
+        let digits = REEL_DIGITS_PER_CHAR * (index + 1);
+        let position = (digits + 1) * REEL_ROWS * step / stop_step;
+        let (glyph, shift) = (position / REEL_ROWS, position % REEL_ROWS);
+
+        let reel_glyph = |glyph: usize, col: usize| match glyph {
+            0 => self.from[index][col],
+            glyph if glyph > digits => self.to[index][col],
+            glyph => font::glyph_col(b'0' + ((index + glyph) % 10) as u8, col),
+        };
+
+        let mut cell = [0x00; CHAR_WIDTH];
+        for (col, dots) in cell.iter_mut().enumerate() {
+            let current = reel_glyph(glyph, col) >> shift;
+            let next = (reel_glyph(glyph + 1, col) as u16) << (REEL_ROWS - shift);
+            *dots = (current | next as u8) & ROWS_MASK;
+        }
+        cell
+    }
+}
+
+// spreads dot indices evenly over the u32 range, so each dot is revealed at a random step
+fn hash(mut value: u32) -> u32 {
+    value = value.wrapping_mul(0x9E37_79B1);
+    value ^= value >> 15;
+    value = value.wrapping_mul(0x85EB_CA77);
+    value ^ (value >> 13)
+}
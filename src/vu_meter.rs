@@ -0,0 +1,178 @@
+// levels are in tenths of a dB relative to full scale, so -6 dB is -60
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VuLayout {
+    // left channel in the top three rows, right channel in the bottom three
+    #[default]
+    Stacked,
+    // left channel grows leftwards from the center, right channel rightwards
+    Split,
+}
+
+const LEFT_ROWS: u8 = 0x07;
+const RIGHT_ROWS: u8 = 0x70;
+const SPLIT_ROWS: u8 = 0x3E;
+
+const DEFAULT_FLOOR: i16 = -400;
+const DEFAULT_HOLD_TICKS: u16 = 30;
+const DEFAULT_DECAY: i16 = 5;
+
+// log2(1 + i/16) in 1/256ths, for the amplitude to dB conversion
+const LOG2_FRACTION: [i32; 17] = [
+    0, 22, 44, 63, 82, 100, 118, 134, 150, 165, 179, 193, 207, 220, 232, 244, 256,
+];
+
+// Two channel level meter with peak hold markers, levels are set with update and the peaks held
+// and decayed by calling tick at a fixed rate.
+pub struct VuMeter {
+    layout: VuLayout,
+    floor: i16,
+    hold_ticks: u16,
+    decay: i16,
+    levels: [i16; 2],
+    peaks: [i16; 2],
+    hold: [u16; 2],
+}
+
+impl VuMeter {
+    // defaults to a -40 dB floor with peaks held for 30 ticks then decaying 0.5 dB per tick
+    pub const fn new(layout: VuLayout) -> Self {
+        VuMeter {
+            layout,
+            floor: DEFAULT_FLOOR,
+            hold_ticks: DEFAULT_HOLD_TICKS,
+            decay: DEFAULT_DECAY,
+            levels: [DEFAULT_FLOOR; 2],
+            peaks: [DEFAULT_FLOOR; 2],
+            hold: [0; 2],
+        }
+    }
+
+    pub fn set_layout(&mut self, layout: VuLayout) {
+        self.layout = layout;
+    }
+
+    // the level shown as an empty meter, levels below it are clamped
+    pub fn set_floor(&mut self, floor: i16) {
+        self.floor = floor.min(-1);
+        for channel in 0..2 {
+            self.levels[channel] = self.levels[channel].max(self.floor);
+            self.peaks[channel] = self.peaks[channel].max(self.floor);
+        }
+    }
+
+    pub fn set_peak_hold(&mut self, hold_ticks: u16, decay_per_tick: i16) {
+        self.hold_ticks = hold_ticks;
+        self.decay = decay_per_tick.max(0);
+    }
+
+    // linear amplitudes, e.g. the absolute peak or RMS of a block of samples, with u16::MAX as
+    // full scale
+    pub fn update(&mut self, left: u16, right: u16) {
+        self.update_db(amplitude_to_db(left), amplitude_to_db(right));
+    }
+
+    pub fn update_db(&mut self, left: i16, right: i16) {
+        for (channel, level) in [left, right].into_iter().enumerate() {
+            let level = level.clamp(self.floor, 0);
+            self.levels[channel] = level;
+            if level >= self.peaks[channel] {
+                self.peaks[channel] = level;
+                self.hold[channel] = self.hold_ticks;
+            }
+        }
+    }
+
+    pub fn tick(&mut self) {
+        for channel in 0..2 {
+            if self.hold[channel] > 0 {
+                self.hold[channel] -= 1;
+            } else {
+                self.peaks[channel] = (self.peaks[channel] - self.decay)
+                    .max(self.levels[channel])
+                    .max(self.floor);
+            }
+        }
+    }
+
+    pub fn levels(&self) -> (i16, i16) {
+        (self.levels[0], self.levels[1])
+    }
+
+    pub fn peaks(&self) -> (i16, i16) {
+        (self.peaks[0], self.peaks[1])
+    }
+
+    pub fn reset(&mut self) {
+        self.levels = [self.floor; 2];
+        self.peaks = [self.floor; 2];
+        self.hold = [0; 2];
+    }
+
+    // Renders both channels into display columns, e.g. for print_cols.
+    pub fn render(&self, cols: &mut [u8]) {
+        cols.fill(0x00);
+
+        match self.layout {
+            VuLayout::Stacked => {
+                let width = cols.len();
+                for (channel, rows) in [LEFT_ROWS, RIGHT_ROWS].into_iter().enumerate() {
+                    let (lit, peak) = self.channel_cols(channel, width);
+                    cols[..lit].iter_mut().for_each(|col| *col |= rows);
+                    if let Some(peak) = peak {
+                        cols[peak] |= rows;
+                    }
+                }
+            }
+            VuLayout::Split => {
+                // the middle column is left blank on odd widths
+                let half = cols.len() / 2;
+                let (rest, right) = cols.split_at_mut(cols.len() - half);
+                let left = &mut rest[..half];
+
+                let (lit, peak) = self.channel_cols(0, half);
+                left[half - lit..].fill(SPLIT_ROWS);
+                if let Some(peak) = peak {
+                    left[half - 1 - peak] = SPLIT_ROWS;
+                }
+
+                let (lit, peak) = self.channel_cols(1, half);
+                right[..lit].fill(SPLIT_ROWS);
+                if let Some(peak) = peak {
+                    right[peak] = SPLIT_ROWS;
+                }
+            }
+        }
+    }
+
+    // columns lit for the channel's level and the column of its peak marker
+    fn channel_cols(&self, channel: usize, width: usize) -> (usize, Option<usize>) {
+        let lit = self.scale(self.levels[channel], width);
+        let peak = self.scale(self.peaks[channel], width);
+        (lit, peak.checked_sub(1))
+    }
+
+    fn scale(&self, level: i16, width: usize) -> usize {
+        let range = -(self.floor as i32);
+        let above_floor = level.clamp(self.floor, 0) as i32 + range;
+        ((above_floor * width as i32 + range / 2) / range) as usize
+    }
+}
+
+// 20 * log10(amplitude / u16::MAX) in tenths of a dB, using a piecewise linear log2
+fn amplitude_to_db(amplitude: u16) -> i16 {
+    if amplitude == 0 {
+        return i16::MIN;
+    }
+
+    let msb = 15 - amplitude.leading_zeros() as i32;
+    let mantissa = ((amplitude as i32) << (15 - msb)) - 0x8000;
+    let index = (mantissa >> 11) as usize;
+    let remainder = mantissa & 0x7FF;
+    let fraction = LOG2_FRACTION[index]
+        + (((LOG2_FRACTION[index + 1] - LOG2_FRACTION[index]) * remainder) >> 11);
+
+    // 20 * log10(2) dB per octave, with the log2 in 1/256ths
+    let log2 = msb * 256 + fraction - 16 * 256;
+    (log2 * 6021 / 25600) as i16
+}
@@ -0,0 +1,172 @@
+use crate::glyph::{Bitmap, Glyph};
+use std::collections::BTreeMap;
+
+struct BoundingBox {
+    width: usize,
+    height: usize,
+    x_off: isize,
+    y_off: isize,
+}
+
+impl BoundingBox {
+    fn parse(args: &[&str], line_num: usize) -> Result<Self, String> {
+        let values = args
+            .iter()
+            .map(|arg| arg.parse::<isize>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("line {line_num}: invalid bounding box: {e}"))?;
+        if values.len() != 4 || values[0] < 0 || values[1] < 0 {
+            return Err(format!("line {line_num}: invalid bounding box"));
+        }
+
+        Ok(BoundingBox {
+            width: values[0] as usize,
+            height: values[1] as usize,
+            x_off: values[2],
+            y_off: values[3],
+        })
+    }
+}
+
+// parses a BDF font and converts every glyph with an 8-bit encoding into a 5x7 glyph,
+// glyphs are aligned to the font's left edge and ascent, anything outside the cell is clipped
+pub fn parse(source: &str) -> Result<BTreeMap<u8, Glyph>, String> {
+    let mut glyphs = BTreeMap::new();
+    let mut font_bbx: Option<BoundingBox> = None;
+    let mut font_ascent: Option<isize> = None;
+
+    let mut encoding: Option<isize> = None;
+    let mut char_bbx: Option<BoundingBox> = None;
+    let mut bitmap: Option<Bitmap> = None;
+    let mut bitmap_row = 0;
+
+    for (index, line) in source.lines().enumerate() {
+        let line_num = index + 1;
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let Some((&keyword, args)) = tokens.split_first() else {
+            continue;
+        };
+
+        if let Some(bitmap) = bitmap.as_mut() {
+            if keyword != "ENDCHAR" {
+                if bitmap_row >= bitmap.height {
+                    return Err(format!("line {line_num}: too many bitmap rows"));
+                }
+                let row = u64::from_str_radix(keyword, 16)
+                    .map_err(|e| format!("line {line_num}: invalid bitmap row: {e}"))?;
+                let row_bits = keyword.len() * 4;
+                if bitmap.width > row_bits {
+                    return Err(format!("line {line_num}: bitmap row too short"));
+                }
+                for x in 0..bitmap.width {
+                    bitmap.set(x, bitmap_row, row & (1 << (row_bits - 1 - x)) != 0);
+                }
+                bitmap_row += 1;
+                continue;
+            }
+        }
+
+        match keyword {
+            "FONTBOUNDINGBOX" => font_bbx = Some(BoundingBox::parse(args, line_num)?),
+            "FONT_ASCENT" => {
+                let ascent = args
+                    .first()
+                    .and_then(|arg| arg.parse().ok())
+                    .ok_or(format!("line {line_num}: invalid FONT_ASCENT"))?;
+                font_ascent = Some(ascent);
+            }
+            "STARTCHAR" => {
+                encoding = None;
+                char_bbx = None;
+            }
+            "ENCODING" => {
+                encoding = args.first().and_then(|arg| arg.parse().ok());
+            }
+            "BBX" => char_bbx = Some(BoundingBox::parse(args, line_num)?),
+            "BITMAP" => {
+                let bbx = char_bbx
+                    .as_ref()
+                    .ok_or(format!("line {line_num}: BITMAP without BBX"))?;
+                bitmap = Some(Bitmap::new(bbx.width, bbx.height));
+                bitmap_row = 0;
+            }
+            "ENDCHAR" => {
+                let (Some(bbx), Some(char_bitmap)) = (char_bbx.take(), bitmap.take()) else {
+                    return Err(format!("line {line_num}: ENDCHAR without BITMAP"));
+                };
+                let code = match encoding.map(u8::try_from) {
+                    Some(Ok(code)) => code,
+                    // glyphs without an 8-bit encoding can't be addressed by the driver
+                    _ => continue,
+                };
+
+                let font_bbx = font_bbx
+                    .as_ref()
+                    .ok_or(format!("line {line_num}: missing FONTBOUNDINGBOX"))?;
+                let ascent = font_ascent.unwrap_or(font_bbx.height as isize + font_bbx.y_off);
+                let x_off = bbx.x_off - font_bbx.x_off;
+                let y_off = ascent - (bbx.y_off + bbx.height as isize);
+
+                let (glyph, clipped) = char_bitmap.to_glyph(x_off, y_off);
+                if clipped {
+                    eprintln!("warning: glyph 0x{code:02X} clipped to 5x7");
+                }
+                glyphs.insert(code, glyph);
+            }
+            _ => {}
+        }
+    }
+
+    if glyphs.is_empty() {
+        return Err("no glyphs found".into());
+    }
+
+    Ok(glyphs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn font(chars: &str) -> String {
+        format!("STARTFONT 2.1\nFONTBOUNDINGBOX 6 9 0 -2\nFONT_ASCENT 7\n{chars}ENDFONT\n")
+    }
+
+    #[test]
+    fn glyph_placed_by_offset_and_ascent() {
+        // a 2x2 block with its bottom 3 rows above the baseline and 1 column in
+        let source = font("STARTCHAR block\nENCODING 65\nBBX 2 2 1 3\nBITMAP\nC0\nC0\nENDCHAR\n");
+        let glyphs = parse(&source).unwrap();
+
+        // top row is ascent - (y_off + height) = 7 - 5 = 2
+        assert_eq!(glyphs[&65].cols, [0x00, 0x0C, 0x0C, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn descender_clipped_below_cell() {
+        // a 1x3 stroke from the baseline down into the descent, only its top row fits
+        let source =
+            font("STARTCHAR bar\nENCODING 66\nBBX 1 3 0 -2\nBITMAP\n80\n80\n80\nENDCHAR\n");
+        let glyphs = parse(&source).unwrap();
+
+        assert_eq!(glyphs[&66].cols, [0x40, 0x00, 0x00, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn glyphs_without_8_bit_encoding_skipped() {
+        let source = font(
+            "STARTCHAR wide\nENCODING 300\nBBX 1 1 0 0\nBITMAP\n80\nENDCHAR\n\
+             STARTCHAR unencoded\nENCODING -1\nBBX 1 1 0 0\nBITMAP\n80\nENDCHAR\n\
+             STARTCHAR dot\nENCODING 46\nBBX 1 1 0 0\nBITMAP\n80\nENDCHAR\n",
+        );
+        let glyphs = parse(&source).unwrap();
+
+        assert_eq!(glyphs.keys().copied().collect::<Vec<_>>(), [46]);
+    }
+
+    #[test]
+    fn too_many_bitmap_rows_rejected() {
+        let source = font("STARTCHAR a\nENCODING 97\nBBX 1 1 0 0\nBITMAP\n80\n80\nENDCHAR\n");
+        assert!(parse(&source).is_err());
+    }
+}
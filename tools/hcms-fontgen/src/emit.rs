@@ -0,0 +1,175 @@
+use crate::glyph::{Glyph, CHAR_WIDTH};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+// ranges of the driver's font subset features, see src/font5x7.rs
+const ASCII_RANGE: (u8, u8) = (0x20, 0x7E);
+const UPPERCASE_RANGE: (u8, u8) = (0x20, 0x5F);
+const DIGITS_RANGE: (u8, u8) = (0x20, 0x3F);
+
+// emits Rust source for a font table in the FONT5X7 layout, the data is defined once and placed by
+// the driver's font_table! macro for both the avr-progmem and default builds, with the font subset
+// features cutting it down to the part of their range the font covers
+pub fn font_source(
+    glyphs: &BTreeMap<u8, Glyph>,
+    first: u8,
+    last: u8,
+    name: &str,
+    input: &str,
+) -> String {
+    let len = (last as usize - first as usize + 2) * CHAR_WIDTH;
+    let range = |(subset_first, subset_last)| {
+        let (first, last) = subset_range(first, last, subset_first, subset_last);
+        format!("(0x{first:02X}, 0x{last:02X})")
+    };
+    let full = range((first, last));
+    let ascii = range(ASCII_RANGE);
+    let uppercase = range(UPPERCASE_RANGE);
+    let digits = range(DIGITS_RANGE);
+
+    let mut out = String::new();
+    writeln!(out, "// Generated by hcms-fontgen from {input}").unwrap();
+    write!(
+        out,
+        r#"
+use crate::font::{{font_subset, font_table}};
+use crate::CHAR_WIDTH;
+
+// the largest enabled subset wins so the font features stay additive, full font if none are enabled
+#[cfg(any(
+    feature = "font_extended",
+    not(any(
+        feature = "font_digits",
+        feature = "font_uppercase",
+        feature = "font_ascii"
+    ))
+))]
+pub(crate) const {name}_RANGE: (u8, u8) = {full};
+#[cfg(all(feature = "font_ascii", not(feature = "font_extended")))]
+pub(crate) const {name}_RANGE: (u8, u8) = {ascii};
+#[cfg(all(
+    feature = "font_uppercase",
+    not(any(feature = "font_ascii", feature = "font_extended"))
+))]
+pub(crate) const {name}_RANGE: (u8, u8) = {uppercase};
+#[cfg(all(
+    feature = "font_digits",
+    not(any(
+        feature = "font_uppercase",
+        feature = "font_ascii",
+        feature = "font_extended"
+    ))
+))]
+pub(crate) const {name}_RANGE: (u8, u8) = {digits};
+
+const {name}_LEN: usize =
+    ({name}_RANGE.1 - {name}_RANGE.0) as usize * CHAR_WIDTH + 2 * CHAR_WIDTH;
+
+font_table! {{
+    pub static {name}: [u8; {name}_LEN] =
+        font_subset(&{name}_DATA, {name}_RANGE.0, {name}_RANGE.1);
+}}
+
+"#
+    )
+    .unwrap();
+    writeln!(out, "pub(crate) const {name}_DATA: [u8; {len}] = [").unwrap();
+    for row in table_rows(glyphs, first, last) {
+        writeln!(out, "    {row}").unwrap();
+    }
+    writeln!(out, "];").unwrap();
+    out
+}
+
+// the part of a subset's range the font covers, or the whole font when it covers none of it
+fn subset_range(first: u8, last: u8, subset_first: u8, subset_last: u8) -> (u8, u8) {
+    let (clamped_first, clamped_last) = (subset_first.max(first), subset_last.min(last));
+    if clamped_first > clamped_last {
+        eprintln!(
+            "warning: font has no glyphs in subset 0x{subset_first:02X}-0x{subset_last:02X}, using the full font"
+        );
+        return (first, last);
+    }
+    (clamped_first, clamped_last)
+}
+
+fn table_rows(glyphs: &BTreeMap<u8, Glyph>, first: u8, last: u8) -> Vec<String> {
+    let mut rows = vec![
+        "// Font metadata: ASCII offset, last ASCII character, don't care, don't care, don't care"
+            .to_string(),
+        format!("{}, // 0x00", format_cols(&[first, last, 0x00, 0x00, 0x00])),
+    ];
+
+    for code in first..=last {
+        let glyph = glyphs.get(&code).copied().unwrap_or_else(|| {
+            eprintln!("warning: no glyph for 0x{code:02X}, using blank");
+            Glyph::default()
+        });
+        rows.push(format!(
+            "{}, // 0x{code:02X}{}",
+            format_cols(&glyph.cols),
+            char_label(code)
+        ));
+    }
+
+    rows
+}
+
+fn format_cols(cols: &[u8]) -> String {
+    cols.iter()
+        .map(|col| format!("0x{col:02X}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn char_label(code: u8) -> String {
+    match code {
+        b' ' => " (space)".to_string(),
+        b'\\' => " (backslash)".to_string(),
+        0x21..=0x7E => format!(" {}", code as char),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_row_holds_range() {
+        let rows = table_rows(&BTreeMap::new(), 0x30, 0x39);
+        assert_eq!(rows[1], "0x30, 0x39, 0x00, 0x00, 0x00, // 0x00");
+        assert_eq!(rows.len(), 2 + 10);
+    }
+
+    #[test]
+    fn glyph_rows_in_code_order() {
+        let mut glyphs = BTreeMap::new();
+        glyphs.insert(
+            b'B',
+            Glyph {
+                cols: [0x7F, 0x49, 0x49, 0x49, 0x36],
+            },
+        );
+        let rows = table_rows(&glyphs, b'A', b'B');
+        assert_eq!(rows[2], "0x00, 0x00, 0x00, 0x00, 0x00, // 0x41 A");
+        assert_eq!(rows[3], "0x7F, 0x49, 0x49, 0x49, 0x36, // 0x42 B");
+    }
+
+    #[test]
+    fn source_declares_table_and_subset_ranges() {
+        let source = font_source(&BTreeMap::new(), 0x20, 0x5A, "FONT5X7", "test.bdf");
+        assert!(source.contains("pub(crate) const FONT5X7_DATA: [u8; 300] = ["));
+        assert!(source.contains("pub(crate) const FONT5X7_RANGE: (u8, u8) = (0x20, 0x5A);"));
+        assert!(source.contains("pub(crate) const FONT5X7_RANGE: (u8, u8) = (0x20, 0x3F);"));
+        assert!(source.contains("font_subset(&FONT5X7_DATA, FONT5X7_RANGE.0, FONT5X7_RANGE.1)"));
+    }
+
+    #[test]
+    fn subset_limited_to_font_range() {
+        assert_eq!(subset_range(0x30, 0x5A, 0x20, 0x7E), (0x30, 0x5A));
+        assert_eq!(subset_range(0x20, 0x7E, 0x20, 0x3F), (0x20, 0x3F));
+        // no overlap falls back to the whole font
+        assert_eq!(subset_range(0x80, 0x99, 0x20, 0x3F), (0x80, 0x99));
+    }
+}
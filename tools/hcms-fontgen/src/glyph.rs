@@ -0,0 +1,100 @@
+pub const CHAR_WIDTH: usize = 5;
+pub const CHAR_HEIGHT: usize = 7;
+
+// column-major glyph, bit 0 of each column is the top row (same layout as FONT5X7)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Glyph {
+    pub cols: [u8; CHAR_WIDTH],
+}
+
+impl Glyph {
+    pub fn set_pixel(&mut self, x: usize, y: usize) {
+        self.cols[x] |= 1 << y;
+    }
+}
+
+// glyph bitmap as decoded from a source file, pixels stored row-major
+pub struct Bitmap {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<bool>,
+}
+
+impl Bitmap {
+    pub fn new(width: usize, height: usize) -> Self {
+        Bitmap {
+            width,
+            height,
+            pixels: vec![false; width * height],
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.pixels[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, lit: bool) {
+        self.pixels[y * self.width + x] = lit;
+    }
+
+    // place the bitmap into a 5x7 cell with its top left corner at (x_off, y_off),
+    // returns the glyph and whether any lit pixels had to be clipped
+    pub fn to_glyph(&self, x_off: isize, y_off: isize) -> (Glyph, bool) {
+        let mut glyph = Glyph::default();
+        let mut clipped = false;
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if !self.get(x, y) {
+                    continue;
+                }
+
+                let cell_x = x as isize + x_off;
+                let cell_y = y as isize + y_off;
+                if (0..CHAR_WIDTH as isize).contains(&cell_x)
+                    && (0..CHAR_HEIGHT as isize).contains(&cell_y)
+                {
+                    glyph.set_pixel(cell_x as usize, cell_y as usize);
+                } else {
+                    clipped = true;
+                }
+            }
+        }
+
+        (glyph, clipped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filled(width: usize, height: usize) -> Bitmap {
+        let mut bitmap = Bitmap::new(width, height);
+        bitmap.pixels.fill(true);
+        bitmap
+    }
+
+    #[test]
+    fn fitting_bitmap_not_clipped() {
+        let (glyph, clipped) = filled(5, 7).to_glyph(0, 0);
+        assert_eq!(glyph.cols, [0x7F; CHAR_WIDTH]);
+        assert!(!clipped);
+    }
+
+    #[test]
+    fn pixels_outside_cell_clipped() {
+        let (glyph, clipped) = filled(2, 2).to_glyph(4, -1);
+        assert_eq!(glyph.cols, [0x00, 0x00, 0x00, 0x00, 0x01]);
+        assert!(clipped);
+    }
+
+    #[test]
+    fn unlit_pixels_outside_cell_not_clipped() {
+        let mut bitmap = Bitmap::new(6, 8);
+        bitmap.set(0, 0, true);
+        let (glyph, clipped) = bitmap.to_glyph(0, 0);
+        assert_eq!(glyph.cols, [0x01, 0x00, 0x00, 0x00, 0x00]);
+        assert!(!clipped);
+    }
+}
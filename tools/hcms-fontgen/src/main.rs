@@ -0,0 +1,183 @@
+mod bdf;
+mod emit;
+mod glyph;
+mod png_sheet;
+
+use png_sheet::SheetLayout;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Converts a BDF font or PNG glyph sheet into an hcms-29xx 5x7 font table.
+
+Usage: hcms-fontgen [OPTIONS] <INPUT>
+
+Options:
+  --format <bdf|png>     Input format (default: from file extension)
+  --range <FIRST-LAST>   Character range to emit, e.g. 0x20-0x7E (default: all glyphs in input)
+  --name <NAME>          Name of the generated static (default: FONT5X7)
+  -o, --output <FILE>    Write Rust source to FILE instead of stdout
+
+PNG options:
+  --cell <WxH>           Size of each glyph cell in the sheet (default: 5x7)
+  --first-code <CODE>    Character code of the top left cell (default: 0x00)
+  --invert               Treat light pixels as lit instead of dark pixels
+";
+
+enum Format {
+    Bdf,
+    Png,
+}
+
+struct Args {
+    input: PathBuf,
+    format: Option<Format>,
+    range: Option<(u8, u8)>,
+    name: String,
+    output: Option<PathBuf>,
+    layout: SheetLayout,
+}
+
+fn parse_code(value: &str) -> Result<u8, String> {
+    let parsed = if let Some(hex) = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        u8::from_str_radix(hex, 16)
+    } else {
+        value.parse()
+    };
+    parsed.map_err(|_| format!("invalid character code '{value}'"))
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut input = None;
+    let mut format = None;
+    let mut range = None;
+    let mut name = "FONT5X7".to_string();
+    let mut output = None;
+    let mut layout = SheetLayout {
+        cell_width: glyph::CHAR_WIDTH,
+        cell_height: glyph::CHAR_HEIGHT,
+        first_code: 0x00,
+        invert: false,
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {arg}"));
+        match arg.as_str() {
+            "-h" | "--help" => {
+                print!("{USAGE}");
+                std::process::exit(0);
+            }
+            "--format" => {
+                format = Some(match value()?.as_str() {
+                    "bdf" => Format::Bdf,
+                    "png" => Format::Png,
+                    other => return Err(format!("unknown format '{other}'")),
+                })
+            }
+            "--range" => {
+                let value = value()?;
+                let (first, last) = value
+                    .split_once('-')
+                    .ok_or(format!("invalid range '{value}'"))?;
+                range = Some((parse_code(first)?, parse_code(last)?));
+            }
+            "--name" => name = value()?,
+            "-o" | "--output" => output = Some(PathBuf::from(value()?)),
+            "--cell" => {
+                let value = value()?;
+                let (width, height) = value
+                    .split_once('x')
+                    .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+                    .ok_or(format!("invalid cell size '{value}'"))?;
+                layout.cell_width = width;
+                layout.cell_height = height;
+            }
+            "--first-code" => layout.first_code = parse_code(&value()?)?,
+            "--invert" => layout.invert = true,
+            _ if arg.starts_with('-') => return Err(format!("unknown option '{arg}'")),
+            _ if input.is_none() => input = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument '{arg}'")),
+        }
+    }
+
+    Ok(Args {
+        input: input.ok_or("missing input file")?,
+        format,
+        range,
+        name,
+        output,
+        layout,
+    })
+}
+
+fn input_format(path: &Path) -> Result<Format, String> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("bdf") => Ok(Format::Bdf),
+        Some(ext) if ext.eq_ignore_ascii_case("png") => Ok(Format::Png),
+        _ => Err(format!(
+            "can't infer format of {}, use --format",
+            path.display()
+        )),
+    }
+}
+
+fn run() -> Result<(), String> {
+    let args = parse_args()?;
+    let format = match args.format {
+        Some(format) => format,
+        None => input_format(&args.input)?,
+    };
+
+    let glyphs = match format {
+        Format::Bdf => {
+            let source = std::fs::read_to_string(&args.input)
+                .map_err(|e| format!("{}: {e}", args.input.display()))?;
+            bdf::parse(&source)?
+        }
+        Format::Png => png_sheet::parse(&args.input, &args.layout)?,
+    };
+
+    // code 0x00 is reserved for the font metadata
+    let (first, last) = match args.range {
+        Some(range) => range,
+        None => (
+            glyphs.range(1..).next().map(|(&code, _)| code).unwrap_or(1),
+            glyphs.keys().next_back().copied().unwrap_or(1),
+        ),
+    };
+    if first == 0 || first > last {
+        return Err(format!(
+            "invalid range 0x{first:02X}-0x{last:02X}, first code must be between 0x01 and last code"
+        ));
+    }
+
+    let input_name = args
+        .input
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let source = emit::font_source(&glyphs, first, last, &args.name, &input_name);
+
+    match args.output {
+        Some(path) => {
+            std::fs::write(&path, source).map_err(|e| format!("{}: {e}", path.display()))?
+        }
+        None => print!("{source}"),
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}\n\n{USAGE}");
+            ExitCode::FAILURE
+        }
+    }
+}
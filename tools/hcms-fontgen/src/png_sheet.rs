@@ -0,0 +1,155 @@
+use crate::glyph::{Bitmap, Glyph};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::Path;
+
+pub struct SheetLayout {
+    pub cell_width: usize,
+    pub cell_height: usize,
+    // code of the glyph in the top left cell, cells are numbered left to right, top to bottom
+    pub first_code: u8,
+    // light pixels are lit instead of dark ones
+    pub invert: bool,
+}
+
+// slices a PNG glyph sheet into cells and converts each cell into a 5x7 glyph,
+// cells are aligned to their top left corner and anything outside 5x7 is clipped
+pub fn parse(path: &Path, layout: &SheetLayout) -> Result<BTreeMap<u8, Glyph>, String> {
+    let image = load_image(path, layout.invert)?;
+    if layout.cell_width == 0 || layout.cell_height == 0 {
+        return Err("cell size must be non-zero".into());
+    }
+
+    let cols = image.width / layout.cell_width;
+    let rows = image.height / layout.cell_height;
+    if cols == 0 || rows == 0 {
+        return Err(format!(
+            "image ({}x{}) is smaller than one {}x{} cell",
+            image.width, image.height, layout.cell_width, layout.cell_height
+        ));
+    }
+
+    let mut glyphs = BTreeMap::new();
+    for cell in 0..cols * rows {
+        let Ok(code) = u8::try_from(layout.first_code as usize + cell) else {
+            break;
+        };
+
+        let cell_x = (cell % cols) * layout.cell_width;
+        let cell_y = (cell / cols) * layout.cell_height;
+        let mut bitmap = Bitmap::new(layout.cell_width, layout.cell_height);
+        for y in 0..layout.cell_height {
+            for x in 0..layout.cell_width {
+                bitmap.set(x, y, image.get(cell_x + x, cell_y + y));
+            }
+        }
+
+        let (glyph, clipped) = bitmap.to_glyph(0, 0);
+        if clipped {
+            eprintln!("warning: glyph 0x{code:02X} clipped to 5x7");
+        }
+        glyphs.insert(code, glyph);
+    }
+
+    Ok(glyphs)
+}
+
+fn load_image(path: &Path, invert: bool) -> Result<Bitmap, String> {
+    let file = File::open(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    let mut decoder = png::Decoder::new(file);
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder
+        .read_info()
+        .map_err(|e| format!("{}: {e}", path.display()))?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut buf)
+        .map_err(|e| format!("{}: {e}", path.display()))?;
+
+    let channels = info.color_type.samples();
+    let has_alpha = matches!(
+        info.color_type,
+        png::ColorType::GrayscaleAlpha | png::ColorType::Rgba
+    );
+
+    let width = info.width as usize;
+    let height = info.height as usize;
+    let mut image = Bitmap::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            let offset = y * info.line_size + x * channels;
+            let pixel = &buf[offset..offset + channels];
+
+            let luminance = if channels >= 3 {
+                (pixel[0] as u32 * 299 + pixel[1] as u32 * 587 + pixel[2] as u32 * 114) / 1000
+            } else {
+                pixel[0] as u32
+            };
+            let opaque = !has_alpha || pixel[channels - 1] >= 128;
+            let dark = luminance < 128;
+
+            // transparent pixels are always background
+            image.set(x, y, opaque && (dark != invert));
+        }
+    }
+
+    Ok(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // writes a grayscale sheet with the given dark pixels on a white background
+    fn write_sheet(name: &str, width: u32, height: u32, dark: &[(u32, u32)]) -> std::path::PathBuf {
+        let mut pixels = vec![0xFF; (width * height) as usize];
+        for &(x, y) in dark {
+            pixels[(y * width + x) as usize] = 0x00;
+        }
+
+        let path =
+            std::env::temp_dir().join(format!("hcms-fontgen-{}-{name}.png", std::process::id()));
+        let mut encoder = png::Encoder::new(File::create(&path).unwrap(), width, height);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .unwrap()
+            .write_image_data(&pixels)
+            .unwrap();
+        path
+    }
+
+    #[test]
+    fn cells_numbered_from_first_code() {
+        // two 6x8 cells, a dot at the top left of the first and the bottom of the second's 5x7 area
+        let path = write_sheet("cells", 12, 8, &[(0, 0), (10, 6)]);
+        let layout = SheetLayout {
+            cell_width: 6,
+            cell_height: 8,
+            first_code: b'A',
+            invert: false,
+        };
+        let glyphs = parse(&path, &layout).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(glyphs.len(), 2);
+        assert_eq!(glyphs[&b'A'].cols, [0x01, 0x00, 0x00, 0x00, 0x00]);
+        assert_eq!(glyphs[&b'B'].cols, [0x00, 0x00, 0x00, 0x00, 0x40]);
+    }
+
+    #[test]
+    fn invert_lights_light_pixels() {
+        let path = write_sheet("invert", 5, 7, &[]);
+        let layout = SheetLayout {
+            cell_width: 5,
+            cell_height: 7,
+            first_code: 0x20,
+            invert: true,
+        };
+        let glyphs = parse(&path, &layout).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(glyphs[&0x20].cols, [0x7F; 5]);
+    }
+}